@@ -0,0 +1,71 @@
+use sixtyfiveohtwo::instructions::addressing::*;
+use sixtyfiveohtwo::instructions::{BNE, BRK, DEX, JMP, LDX, STA, TXA};
+use sixtyfiveohtwo::program::ProgramBuilder;
+use sixtyfiveohtwo::{asm, disasm, Bus, Cpu};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+  // Programs can also be written as assembly source
+  let assembly = asm::assemble(
+    "
+      .org $0600
+      LDX #$08          ; count down from eight
+      LDA #%00000001
+    loop:
+      ASL A
+      STA $0200,X
+      DEX
+      BNE loop
+      JMP (vector)      ; forward references resolve in a second pass
+    vector:
+      .word done
+    done:
+      BRK
+    ",
+  )?;
+
+  let mut cpu = Cpu::new();
+  cpu.load(assembly.origin, &assembly.bytes);
+  cpu.registers.pc.value = assembly.origin;
+  cpu.run_until(assembly.origin + assembly.bytes.len() as u16 - 1)?;
+
+  println!("{}", cpu.registers);
+  println!("$0208: ${:02X}", cpu.bus.read(0x0208));
+
+  print!("{}", assembly.symbol_table());
+
+  // And listed back out again
+  let end = assembly.origin + assembly.bytes.len() as u16 - 1;
+  for line in
+    disasm::disassemble(&cpu.bus, assembly.origin, end, cpu.variant, false)
+  {
+    println!("{}", line);
+  }
+
+  // Or built from typed instructions, with labels in place of offsets
+  let program = ProgramBuilder::new(0x0600)
+    .push(LDX(Immediate(0x08)))
+    .label("loop")
+    .push(TXA)
+    .absolute("table", |address| STA(AbsoluteX(address)))
+    .push(DEX)
+    .branch("loop", BNE)
+    .absolute("done", |address| JMP(Absolute(address)))
+    .label("table")
+    .data(&[0; 9])
+    .label("done")
+    .push(BRK)
+    .build()?;
+
+  let mut cpu = Cpu::new();
+  cpu.load(program.origin, &program.bytes);
+  cpu.registers.pc.value = program.origin;
+  cpu.run_until(program.symbols["done"])?;
+
+  print!("{}", program.symbol_table());
+  println!(
+    "table+8: ${:02X}",
+    cpu.bus.read(program.symbols["table"] + 8)
+  );
+
+  Ok(())
+}
@@ -0,0 +1,34 @@
+use sixtyfiveohtwo::instructions::addressing::*;
+use sixtyfiveohtwo::instructions::cmos::{BRA, PHX, PLY, STP, STZ, TSB};
+use sixtyfiveohtwo::instructions::interp::{DecodeError, Variant};
+use sixtyfiveohtwo::instructions::{Renderable, LDA};
+use sixtyfiveohtwo::Cpu;
+
+fn main() -> Result<(), DecodeError> {
+  // The 65C02 adds instructions of its own and STP halts the clock
+  let program = [
+    LDA(Immediate(0x81)).render(),
+    TSB(ZeroPage(0x20)).render(),
+    STZ(ZeroPage(0x21)).render(),
+    LDA(ZeroPageIndirect(0x30)).render(),
+    PHX.render(),
+    PLY.render(),
+    BRA(0).render(),
+    STP.render(),
+  ]
+  .concat();
+
+  let mut cpu = Cpu::new();
+  cpu.variant = Variant::Wdc65C02;
+  cpu.load(0x0020, &[0x0C, 0xFF]);
+  cpu.load(0x0030, &[0x20, 0x00]);
+  cpu.load(0x0600, &program);
+  cpu.registers.pc.value = 0x0600;
+  cpu.registers.x.value = 0x07;
+  cpu.run_until(0x0000)?;
+
+  println!("{}", cpu.registers);
+  println!("Halt: {:?}", cpu.halt);
+
+  Ok(())
+}
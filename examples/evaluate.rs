@@ -0,0 +1,52 @@
+use sixtyfiveohtwo::instructions::addressing::*;
+use sixtyfiveohtwo::instructions::interp::DecodeError;
+use sixtyfiveohtwo::instructions::{
+  Instruction, Renderable, ADC, ASL, INY, LDA, LDX, LDY, STX, TAX,
+};
+use sixtyfiveohtwo::{evaluate, Cpu, Memory, Registers};
+
+fn main() -> Result<(), DecodeError> {
+  let mut memory = Memory::new();
+  let mut registers = Registers::default();
+
+  evaluate!(
+    &mut memory,
+    &mut registers,
+    LDA(Immediate(0)),  // LDA #$0
+    ADC(Immediate(5)),  // ADC #$5
+    ASL(Accumulator),   // ASL A
+    TAX,                // TAX
+    STX(Absolute(100)), // STX $100
+    LDX(Immediate(1)),  // LDX #$1
+    // LDY(AbsoluteY(99)), // LDY $99,Y (This results in a compilation error,
+    //                     //            as this is an invalid instruction.)
+    LDY(AbsoluteX(99)), // LDY $99,X
+    INY                 // INY
+  );
+
+  println!("{}", registers);
+
+  // The same program, assembled into memory and run by the CPU
+  let program = [
+    LDA(Immediate(0)).render(),
+    ADC(Immediate(5)).render(),
+    ASL(Accumulator).render(),
+    TAX.render(),
+    STX(Absolute(100)).render(),
+    LDX(Immediate(1)).render(),
+    LDY(AbsoluteX(99)).render(),
+    INY.render(),
+  ]
+  .concat();
+
+  let mut cpu = Cpu::new();
+  cpu.load(0x0600, &program);
+  cpu.registers.pc.value = 0x0600;
+  cpu.run_for(1)?;
+  cpu.run_until(0x0600 + program.len() as u16)?;
+
+  println!("{}", cpu.registers);
+  println!("Cycles: {}", cpu.cycles);
+
+  Ok(())
+}
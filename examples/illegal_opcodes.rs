@@ -0,0 +1,40 @@
+use sixtyfiveohtwo::cpu::IllegalOpcodePolicy;
+use sixtyfiveohtwo::instructions::addressing::*;
+use sixtyfiveohtwo::instructions::interp::DecodeError;
+use sixtyfiveohtwo::instructions::undocumented::{DCP, LAX, SAX};
+use sixtyfiveohtwo::instructions::Renderable;
+use sixtyfiveohtwo::{Bus, Cpu};
+
+fn main() -> Result<(), DecodeError> {
+  // Opcodes the decoder does not know can be skipped or handed to a trap
+  let mut cpu = Cpu::new();
+  cpu.load(0x0000, &[0x02, 0x02]);
+  cpu.illegal_opcodes = IllegalOpcodePolicy::Nop;
+  cpu.step()?;
+  cpu.illegal_opcodes =
+    IllegalOpcodePolicy::Trap(Box::new(|_bus, registers, error| {
+      println!("Trapped: {}", error);
+      registers.pc.value += 1;
+    }));
+  cpu.step()?;
+
+  // Stable undocumented opcodes decode once the CPU is told to expect them
+  let program = [
+    LAX(ZeroPage(0x10)).render(),
+    SAX(Absolute(0x0200)).render(),
+    DCP(ZeroPage(0x10)).render(),
+  ]
+  .concat();
+
+  let mut cpu = Cpu::new();
+  cpu.undocumented_opcodes = true;
+  cpu.load(0x0010, &[0x42]);
+  cpu.load(0x0600, &program);
+  cpu.registers.pc.value = 0x0600;
+  cpu.run_until(0x0600 + program.len() as u16)?;
+
+  println!("{}", cpu.registers);
+  println!("$0010: ${:02X}", cpu.bus.read(0x0010));
+
+  Ok(())
+}
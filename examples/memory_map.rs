@@ -0,0 +1,52 @@
+use sixtyfiveohtwo::instructions::addressing::*;
+use sixtyfiveohtwo::instructions::interp::{DecodeError, Variant};
+use sixtyfiveohtwo::instructions::{Renderable, JMP, LDA, RTI, STA};
+use sixtyfiveohtwo::memory::map::MemoryMap;
+use sixtyfiveohtwo::{Bus, Cpu};
+
+fn main() -> Result<(), DecodeError> {
+  // An NES style layout: 2 KiB of RAM mirrored up to $1FFF, eight device
+  // registers mirrored up to $3FFF and a 16 KiB ROM mirrored up to $FFFF
+  let mut rom = vec![0; 0x4000];
+  let program = [
+    LDA(Immediate(0x1E)).render(),
+    STA(Absolute(0x2001)).render(),
+    STA(Absolute(0x0800)).render(),
+    JMP(Absolute(0xC008)).render(),
+  ]
+  .concat();
+  rom[..program.len()].copy_from_slice(&program);
+  // Interrupt handler at $C010
+  rom[0x10..0x11].copy_from_slice(&RTI.render());
+  // NMI, RESET and IRQ vectors
+  rom[0x3FFA..].copy_from_slice(&[0x10, 0xC0, 0x00, 0xC0, 0x10, 0xC0]);
+
+  let map = MemoryMap::builder()
+    .ram(0x0000, 0x1FFF, 0x0800)
+    .io(
+      0x2000,
+      0x3FFF,
+      |_addr| 0,
+      |addr, value| println!("${:04X} <- ${:02X}", 0x2000 + (addr & 7), value),
+    )
+    .rom(0x8000, 0xFFFF, &rom)
+    .on_rom_write(|addr, value| {
+      eprintln!("Ignored write of ${:02X} to ROM at ${:04X}", value, addr)
+    })
+    .build();
+
+  let mut cpu = Cpu::with_bus(map);
+  cpu.variant = Variant::Ricoh2A03;
+  cpu.reset();
+  cpu.run_for(4)?;
+
+  // Interrupts are serviced between instructions, with IRQ masked by reset
+  cpu.nmi();
+  cpu.irq(true);
+  cpu.run_for(2)?;
+  println!("PC after NMI: ${:04X}", cpu.registers.pc.value);
+
+  println!("RAM $0000: ${:02X}", cpu.bus.read(0x0000));
+
+  Ok(())
+}
@@ -0,0 +1,345 @@
+use super::{Address, ErrorKind, Operand};
+use crate::instructions::addressing::*;
+use crate::instructions::*;
+use std::convert::TryFrom;
+
+pub(super) const MNEMONICS: [&str; 56] = [
+  "ADC", "AND", "ASL", "BCC", "BCS", "BEQ", "BIT", "BMI", "BNE", "BPL", "BRK",
+  "BVC", "BVS", "CLC", "CLD", "CLI", "CLV", "CMP", "CPX", "CPY", "DEC", "DEX",
+  "DEY", "EOR", "INC", "INX", "INY", "JMP", "JSR", "LDA", "LDX", "LDY", "LSR",
+  "NOP", "ORA", "PHA", "PHP", "PLA", "PLP", "ROL", "ROR", "RTI", "RTS", "SBC",
+  "SEC", "SED", "SEI", "STA", "STX", "STY", "TAX", "TAY", "TSX", "TXA", "TXS",
+  "TYA",
+];
+
+const BRANCHES: [&str; 8] =
+  ["BCC", "BCS", "BEQ", "BMI", "BNE", "BPL", "BVC", "BVS"];
+
+// Encode one instruction at `pc` by building the typed instruction and
+// rendering it. Zero page forms are preferred where the operand allows them.
+pub(super) fn encode(
+  mnemonic: &str,
+  operand: Operand,
+  pc: u16,
+  first_pass: bool,
+) -> Result<Vec<u8>, ErrorKind> {
+  if !MNEMONICS.contains(&mnemonic) {
+    return Err(ErrorKind::UnknownMnemonic(mnemonic.to_string()));
+  }
+
+  let bytes = match operand {
+    Operand::Implied => implied(mnemonic).or_else(|| accumulator(mnemonic)),
+    Operand::Accumulator => accumulator(mnemonic),
+    Operand::Immediate(value) => immediate(mnemonic, value),
+    Operand::Direct(address) if BRANCHES.contains(&mnemonic) => {
+      // Branches wrap around the address space like the program counter.
+      let offset = address.value.wrapping_sub(pc.wrapping_add(2)) as i16;
+      let offset = i8::try_from(offset)
+        .map_err(|_| ErrorKind::BranchOutOfRange(i32::from(offset)))?;
+      relative(mnemonic, offset)
+    }
+    Operand::Direct(address) => address
+      .zero_page()
+      .and_then(|value| zero_page(mnemonic, value))
+      .or_else(|| absolute(mnemonic, address.value)),
+    Operand::DirectX(address) => match address
+      .zero_page()
+      .and_then(|value| zero_page_x(mnemonic, value))
+      .or_else(|| absolute_x(mnemonic, address.value))
+    {
+      Some(bytes) => Some(bytes),
+      None => zero_page_only(mnemonic, address, first_pass, zero_page_x)?,
+    },
+    Operand::DirectY(address) => match address
+      .zero_page()
+      .and_then(|value| zero_page_y(mnemonic, value))
+      .or_else(|| absolute_y(mnemonic, address.value))
+    {
+      Some(bytes) => Some(bytes),
+      None => zero_page_only(mnemonic, address, first_pass, zero_page_y)?,
+    },
+    Operand::Indirect(value) => indirect(mnemonic, value),
+    Operand::IndexedIndirect(value) => indexed_indirect(mnemonic, value),
+    Operand::IndirectIndexed(value) => indirect_indexed(mnemonic, value),
+  };
+
+  bytes.ok_or_else(|| ErrorKind::InvalidOperand(mnemonic.to_string()))
+}
+
+// STY zp,X and STX zp,Y have no absolute form, so an operand sized as
+// absolute, like a forward reference, takes the zero page one and has to
+// resolve there.
+fn zero_page_only(
+  mnemonic: &str,
+  address: Address,
+  first_pass: bool,
+  encode: fn(&str, u8) -> Option<Vec<u8>>,
+) -> Result<Option<Vec<u8>>, ErrorKind> {
+  if encode(mnemonic, 0).is_none() {
+    return Ok(None);
+  }
+
+  let value = if first_pass {
+    // Only the length matters until every label is known.
+    address.value as u8
+  } else {
+    u8::try_from(address.value)
+      .map_err(|_| ErrorKind::ValueOutOfRange(address.value))?
+  };
+  Ok(encode(mnemonic, value))
+}
+
+fn implied(mnemonic: &str) -> Option<Vec<u8>> {
+  let bytes = match mnemonic {
+    "BRK" => BRK.render(),
+    "CLC" => CLC.render(),
+    "SEC" => SEC.render(),
+    "CLI" => CLI.render(),
+    "SEI" => SEI.render(),
+    "CLV" => CLV.render(),
+    "CLD" => CLD.render(),
+    "SED" => SED.render(),
+    "NOP" => NOP.render(),
+    "TAX" => TAX.render(),
+    "TXA" => TXA.render(),
+    "DEX" => DEX.render(),
+    "INX" => INX.render(),
+    "TAY" => TAY.render(),
+    "TYA" => TYA.render(),
+    "DEY" => DEY.render(),
+    "INY" => INY.render(),
+    "RTI" => RTI.render(),
+    "RTS" => RTS.render(),
+    "TXS" => TXS.render(),
+    "TSX" => TSX.render(),
+    "PHA" => PHA.render(),
+    "PLA" => PLA.render(),
+    "PHP" => PHP.render(),
+    "PLP" => PLP.render(),
+    _ => return None,
+  };
+
+  Some(bytes)
+}
+
+fn accumulator(mnemonic: &str) -> Option<Vec<u8>> {
+  let bytes = match mnemonic {
+    "ASL" => ASL(Accumulator).render(),
+    "LSR" => LSR(Accumulator).render(),
+    "ROL" => ROL(Accumulator).render(),
+    "ROR" => ROR(Accumulator).render(),
+    _ => return None,
+  };
+
+  Some(bytes)
+}
+
+fn immediate(mnemonic: &str, value: u8) -> Option<Vec<u8>> {
+  let bytes = match mnemonic {
+    "ADC" => ADC(Immediate(value)).render(),
+    "AND" => AND(Immediate(value)).render(),
+    "CMP" => CMP(Immediate(value)).render(),
+    "CPX" => CPX(Immediate(value)).render(),
+    "CPY" => CPY(Immediate(value)).render(),
+    "EOR" => EOR(Immediate(value)).render(),
+    "LDA" => LDA(Immediate(value)).render(),
+    "LDX" => LDX(Immediate(value)).render(),
+    "LDY" => LDY(Immediate(value)).render(),
+    "ORA" => ORA(Immediate(value)).render(),
+    "SBC" => SBC(Immediate(value)).render(),
+    _ => return None,
+  };
+
+  Some(bytes)
+}
+
+fn zero_page(mnemonic: &str, value: u8) -> Option<Vec<u8>> {
+  let bytes = match mnemonic {
+    "ADC" => ADC(ZeroPage(value)).render(),
+    "AND" => AND(ZeroPage(value)).render(),
+    "ASL" => ASL(ZeroPage(value)).render(),
+    "BIT" => BIT(ZeroPage(value)).render(),
+    "CMP" => CMP(ZeroPage(value)).render(),
+    "CPX" => CPX(ZeroPage(value)).render(),
+    "CPY" => CPY(ZeroPage(value)).render(),
+    "DEC" => DEC(ZeroPage(value)).render(),
+    "EOR" => EOR(ZeroPage(value)).render(),
+    "INC" => INC(ZeroPage(value)).render(),
+    "LDA" => LDA(ZeroPage(value)).render(),
+    "LDX" => LDX(ZeroPage(value)).render(),
+    "LDY" => LDY(ZeroPage(value)).render(),
+    "LSR" => LSR(ZeroPage(value)).render(),
+    "ORA" => ORA(ZeroPage(value)).render(),
+    "ROL" => ROL(ZeroPage(value)).render(),
+    "ROR" => ROR(ZeroPage(value)).render(),
+    "SBC" => SBC(ZeroPage(value)).render(),
+    "STA" => STA(ZeroPage(value)).render(),
+    "STX" => STX(ZeroPage(value)).render(),
+    "STY" => STY(ZeroPage(value)).render(),
+    _ => return None,
+  };
+
+  Some(bytes)
+}
+
+fn zero_page_x(mnemonic: &str, value: u8) -> Option<Vec<u8>> {
+  let bytes = match mnemonic {
+    "ADC" => ADC(ZeroPageX(value)).render(),
+    "AND" => AND(ZeroPageX(value)).render(),
+    "ASL" => ASL(ZeroPageX(value)).render(),
+    "CMP" => CMP(ZeroPageX(value)).render(),
+    "DEC" => DEC(ZeroPageX(value)).render(),
+    "EOR" => EOR(ZeroPageX(value)).render(),
+    "INC" => INC(ZeroPageX(value)).render(),
+    "LDA" => LDA(ZeroPageX(value)).render(),
+    "LDY" => LDY(ZeroPageX(value)).render(),
+    "LSR" => LSR(ZeroPageX(value)).render(),
+    "ORA" => ORA(ZeroPageX(value)).render(),
+    "ROL" => ROL(ZeroPageX(value)).render(),
+    "ROR" => ROR(ZeroPageX(value)).render(),
+    "SBC" => SBC(ZeroPageX(value)).render(),
+    "STA" => STA(ZeroPageX(value)).render(),
+    "STY" => STY(ZeroPageX(value)).render(),
+    _ => return None,
+  };
+
+  Some(bytes)
+}
+
+fn zero_page_y(mnemonic: &str, value: u8) -> Option<Vec<u8>> {
+  let bytes = match mnemonic {
+    "LDX" => LDX(ZeroPageY(value)).render(),
+    "STX" => STX(ZeroPageY(value)).render(),
+    _ => return None,
+  };
+
+  Some(bytes)
+}
+
+fn absolute(mnemonic: &str, value: u16) -> Option<Vec<u8>> {
+  let bytes = match mnemonic {
+    "ADC" => ADC(Absolute(value)).render(),
+    "AND" => AND(Absolute(value)).render(),
+    "ASL" => ASL(Absolute(value)).render(),
+    "BIT" => BIT(Absolute(value)).render(),
+    "CMP" => CMP(Absolute(value)).render(),
+    "CPX" => CPX(Absolute(value)).render(),
+    "CPY" => CPY(Absolute(value)).render(),
+    "DEC" => DEC(Absolute(value)).render(),
+    "EOR" => EOR(Absolute(value)).render(),
+    "INC" => INC(Absolute(value)).render(),
+    "JMP" => JMP(Absolute(value)).render(),
+    "JSR" => JSR(value).render(),
+    "LDA" => LDA(Absolute(value)).render(),
+    "LDX" => LDX(Absolute(value)).render(),
+    "LDY" => LDY(Absolute(value)).render(),
+    "LSR" => LSR(Absolute(value)).render(),
+    "ORA" => ORA(Absolute(value)).render(),
+    "ROL" => ROL(Absolute(value)).render(),
+    "ROR" => ROR(Absolute(value)).render(),
+    "SBC" => SBC(Absolute(value)).render(),
+    "STA" => STA(Absolute(value)).render(),
+    "STX" => STX(Absolute(value)).render(),
+    "STY" => STY(Absolute(value)).render(),
+    _ => return None,
+  };
+
+  Some(bytes)
+}
+
+fn absolute_x(mnemonic: &str, value: u16) -> Option<Vec<u8>> {
+  let bytes = match mnemonic {
+    "ADC" => ADC(AbsoluteX(value)).render(),
+    "AND" => AND(AbsoluteX(value)).render(),
+    "ASL" => ASL(AbsoluteX(value)).render(),
+    "CMP" => CMP(AbsoluteX(value)).render(),
+    "DEC" => DEC(AbsoluteX(value)).render(),
+    "EOR" => EOR(AbsoluteX(value)).render(),
+    "INC" => INC(AbsoluteX(value)).render(),
+    "LDA" => LDA(AbsoluteX(value)).render(),
+    "LDY" => LDY(AbsoluteX(value)).render(),
+    "LSR" => LSR(AbsoluteX(value)).render(),
+    "ORA" => ORA(AbsoluteX(value)).render(),
+    "ROL" => ROL(AbsoluteX(value)).render(),
+    "ROR" => ROR(AbsoluteX(value)).render(),
+    "SBC" => SBC(AbsoluteX(value)).render(),
+    "STA" => STA(AbsoluteX(value)).render(),
+    _ => return None,
+  };
+
+  Some(bytes)
+}
+
+fn absolute_y(mnemonic: &str, value: u16) -> Option<Vec<u8>> {
+  let bytes = match mnemonic {
+    "ADC" => ADC(AbsoluteY(value)).render(),
+    "AND" => AND(AbsoluteY(value)).render(),
+    "CMP" => CMP(AbsoluteY(value)).render(),
+    "EOR" => EOR(AbsoluteY(value)).render(),
+    "LDA" => LDA(AbsoluteY(value)).render(),
+    "LDX" => LDX(AbsoluteY(value)).render(),
+    "ORA" => ORA(AbsoluteY(value)).render(),
+    "SBC" => SBC(AbsoluteY(value)).render(),
+    "STA" => STA(AbsoluteY(value)).render(),
+    _ => return None,
+  };
+
+  Some(bytes)
+}
+
+fn indirect(mnemonic: &str, value: u16) -> Option<Vec<u8>> {
+  let bytes = match mnemonic {
+    "JMP" => JMP(Indirect(value)).render(),
+    _ => return None,
+  };
+
+  Some(bytes)
+}
+
+fn indexed_indirect(mnemonic: &str, value: u8) -> Option<Vec<u8>> {
+  let bytes = match mnemonic {
+    "ADC" => ADC(IndexedIndirect(value)).render(),
+    "AND" => AND(IndexedIndirect(value)).render(),
+    "CMP" => CMP(IndexedIndirect(value)).render(),
+    "EOR" => EOR(IndexedIndirect(value)).render(),
+    "LDA" => LDA(IndexedIndirect(value)).render(),
+    "ORA" => ORA(IndexedIndirect(value)).render(),
+    "SBC" => SBC(IndexedIndirect(value)).render(),
+    "STA" => STA(IndexedIndirect(value)).render(),
+    _ => return None,
+  };
+
+  Some(bytes)
+}
+
+fn indirect_indexed(mnemonic: &str, value: u8) -> Option<Vec<u8>> {
+  let bytes = match mnemonic {
+    "ADC" => ADC(IndirectIndexed(value)).render(),
+    "AND" => AND(IndirectIndexed(value)).render(),
+    "CMP" => CMP(IndirectIndexed(value)).render(),
+    "EOR" => EOR(IndirectIndexed(value)).render(),
+    "LDA" => LDA(IndirectIndexed(value)).render(),
+    "ORA" => ORA(IndirectIndexed(value)).render(),
+    "SBC" => SBC(IndirectIndexed(value)).render(),
+    "STA" => STA(IndirectIndexed(value)).render(),
+    _ => return None,
+  };
+
+  Some(bytes)
+}
+
+fn relative(mnemonic: &str, offset: i8) -> Option<Vec<u8>> {
+  let bytes = match mnemonic {
+    "BPL" => BPL(offset).render(),
+    "BMI" => BMI(offset).render(),
+    "BVC" => BVC(offset).render(),
+    "BVS" => BVS(offset).render(),
+    "BCC" => BCC(offset).render(),
+    "BCS" => BCS(offset).render(),
+    "BNE" => BNE(offset).render(),
+    "BEQ" => BEQ(offset).render(),
+    _ => return None,
+  };
+
+  Some(bytes)
+}
@@ -0,0 +1,460 @@
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+
+mod encode;
+
+// A value written in the source, remembering whether it was written wide
+// enough to force absolute addressing (e.g. `$0012`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Address {
+  pub value: u16,
+  pub wide: bool,
+}
+
+impl Address {
+  fn zero_page(self) -> Option<u8> {
+    if self.wide {
+      None
+    } else {
+      u8::try_from(self.value).ok()
+    }
+  }
+}
+
+// Operand syntax, before it is matched to an addressing mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operand {
+  Implied,
+  Accumulator,
+  Immediate(u8),
+  Direct(Address),
+  DirectX(Address),
+  DirectY(Address),
+  Indirect(u16),
+  IndexedIndirect(u8),
+  IndirectIndexed(u8),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+  Syntax(String),
+  UnknownMnemonic(String),
+  UnknownDirective(String),
+  InvalidOperand(String),
+  UndefinedLabel(String),
+  DuplicateLabel(String),
+  ValueOutOfRange(u16),
+  BranchOutOfRange(i32),
+  OriginBackwards(u16),
+}
+
+impl std::fmt::Display for ErrorKind {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      ErrorKind::Syntax(text) => write!(f, "Cannot parse `{}`", text),
+      ErrorKind::UnknownMnemonic(mnemonic) => {
+        write!(f, "Unknown instruction {}", mnemonic)
+      }
+      ErrorKind::UnknownDirective(directive) => {
+        write!(f, "Unknown directive .{}", directive)
+      }
+      ErrorKind::InvalidOperand(mnemonic) => {
+        write!(f, "Addressing mode not available for {}", mnemonic)
+      }
+      ErrorKind::UndefinedLabel(label) => {
+        write!(f, "Undefined label {}", label)
+      }
+      ErrorKind::DuplicateLabel(label) => {
+        write!(f, "Label {} is already defined", label)
+      }
+      ErrorKind::ValueOutOfRange(value) => {
+        write!(f, "Value ${:X} does not fit in a byte", value)
+      }
+      ErrorKind::BranchOutOfRange(offset) => {
+        write!(f, "Branch offset {} is out of range", offset)
+      }
+      ErrorKind::OriginBackwards(origin) => {
+        write!(f, "Origin ${:04X} is behind the current address", origin)
+      }
+    }
+  }
+}
+
+impl std::error::Error for ErrorKind {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AsmError {
+  // One based, as shown by editors.
+  pub line: usize,
+  pub kind: ErrorKind,
+}
+
+impl std::fmt::Display for AsmError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "Line {}: {}", self.line, self.kind)
+  }
+}
+
+impl std::error::Error for AsmError {}
+
+// Assembled bytes, to be loaded at `origin`, and the address of each label.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Assembly {
+  pub origin: u16,
+  pub bytes: Vec<u8>,
+  pub symbols: BTreeMap<String, u16>,
+}
+
+impl Assembly {
+  // Symbols in address order, one `$0600 start` per line.
+  pub fn symbol_table(&self) -> String {
+    let mut symbols: Vec<(&String, &u16)> = self.symbols.iter().collect();
+    symbols.sort_by_key(|(_, address)| **address);
+
+    symbols
+      .iter()
+      .map(|(label, address)| format!("${:04X} {}\n", address, label))
+      .collect()
+  }
+}
+
+// Assemble 6502 source into a binary image in two passes. The first only
+// works out where each label lands, so the second can encode references to
+// labels defined further down.
+pub fn assemble(source: &str) -> Result<Assembly, AsmError> {
+  let mut first = Assembler {
+    first_pass: true,
+    ..Assembler::default()
+  };
+  first.assemble(source)?;
+
+  let mut second = Assembler {
+    labels: first.labels,
+    ..Assembler::default()
+  };
+  second.assemble(source)?;
+
+  Ok(Assembly {
+    origin: second.origin.unwrap_or(0),
+    bytes: second.bytes,
+    symbols: second
+      .labels
+      .into_iter()
+      .map(|(label, (address, _))| (label, address))
+      .collect(),
+  })
+}
+
+#[derive(Default)]
+struct Assembler {
+  origin: Option<u16>,
+  bytes: Vec<u8>,
+  // Address and source line of each label.
+  labels: HashMap<String, (u16, usize)>,
+  first_pass: bool,
+  line: usize,
+}
+
+impl Assembler {
+  fn assemble(&mut self, source: &str) -> Result<(), AsmError> {
+    for (index, line) in source.lines().enumerate() {
+      self.line = index + 1;
+      self.line(line).map_err(|kind| AsmError {
+        line: index + 1,
+        kind,
+      })?;
+    }
+
+    Ok(())
+  }
+
+  fn pc(&self) -> u16 {
+    self
+      .origin
+      .unwrap_or(0)
+      .wrapping_add(self.bytes.len() as u16)
+  }
+
+  fn line(&mut self, line: &str) -> Result<(), ErrorKind> {
+    let line = match line.find(';') {
+      Some(comment) => &line[..comment],
+      None => line,
+    };
+    let mut line = line.trim();
+
+    if let Some(colon) = line.find(':') {
+      let label = &line[..colon];
+      if !is_identifier(label) {
+        return Err(ErrorKind::Syntax(line.to_string()));
+      }
+      if self.first_pass
+        && self
+          .labels
+          .insert(label.to_string(), (self.pc(), self.line))
+          .is_some()
+      {
+        return Err(ErrorKind::DuplicateLabel(label.to_string()));
+      }
+      line = line[colon + 1..].trim();
+    }
+    if line.is_empty() {
+      return Ok(());
+    }
+
+    let (word, rest) = match line.find(char::is_whitespace) {
+      Some(space) => (&line[..space], line[space..].trim()),
+      None => (line, ""),
+    };
+
+    if let Some(directive) = word.strip_prefix('.') {
+      self.directive(&directive.to_lowercase(), rest)
+    } else {
+      let operand = self.operand(rest)?;
+      let bytes = encode::encode(
+        &word.to_uppercase(),
+        operand,
+        self.pc(),
+        self.first_pass,
+      )?;
+      self.bytes.extend(bytes);
+      Ok(())
+    }
+  }
+
+  fn directive(
+    &mut self,
+    directive: &str,
+    args: &str,
+  ) -> Result<(), ErrorKind> {
+    match directive {
+      "org" => {
+        let origin = self.value(args)?.value;
+        if self.bytes.is_empty() {
+          self.origin = Some(origin);
+        } else if origin < self.pc() {
+          return Err(ErrorKind::OriginBackwards(origin));
+        } else {
+          let padding = origin - self.pc();
+          self.bytes.extend(vec![0; usize::from(padding)]);
+        }
+      }
+      "byte" => {
+        for arg in args.split(',') {
+          let value = self.byte(arg.trim())?;
+          self.bytes.push(value);
+        }
+      }
+      "word" => {
+        for arg in args.split(',') {
+          let value = self.value(arg.trim())?.value;
+          self.bytes.extend(value.to_le_bytes());
+        }
+      }
+      _ => return Err(ErrorKind::UnknownDirective(directive.to_string())),
+    }
+
+    Ok(())
+  }
+
+  fn operand(&self, text: &str) -> Result<Operand, ErrorKind> {
+    let text: String = text.split_whitespace().collect();
+    let upper = text.to_uppercase();
+
+    let operand = if text.is_empty() {
+      Operand::Implied
+    } else if upper == "A" {
+      Operand::Accumulator
+    } else if let Some(value) = text.strip_prefix('#') {
+      Operand::Immediate(self.byte(value)?)
+    } else if upper.starts_with('(') && upper.ends_with(",X)") {
+      Operand::IndexedIndirect(self.byte(&text[1..text.len() - 3])?)
+    } else if upper.starts_with('(') && upper.ends_with("),Y") {
+      Operand::IndirectIndexed(self.byte(&text[1..text.len() - 3])?)
+    } else if upper.starts_with('(') && upper.ends_with(')') {
+      Operand::Indirect(self.value(&text[1..text.len() - 1])?.value)
+    } else if upper.ends_with(",X") {
+      Operand::DirectX(self.value(&text[..text.len() - 2])?)
+    } else if upper.ends_with(",Y") {
+      Operand::DirectY(self.value(&text[..text.len() - 2])?)
+    } else {
+      Operand::Direct(self.value(&text)?)
+    };
+
+    Ok(operand)
+  }
+
+  fn byte(&self, text: &str) -> Result<u8, ErrorKind> {
+    let value = self.value(text)?.value;
+    if self.first_pass {
+      // Only the length matters until every label is known.
+      return Ok(value as u8);
+    }
+    u8::try_from(value).map_err(|_| ErrorKind::ValueOutOfRange(value))
+  }
+
+  // A number in hex (`$FF`), binary (`%1010`) or decimal, or a label.
+  fn value(&self, text: &str) -> Result<Address, ErrorKind> {
+    let syntax = || ErrorKind::Syntax(text.to_string());
+
+    let (digits, radix, narrow) = if let Some(hex) = text.strip_prefix('$') {
+      (hex, 16, 2)
+    } else if let Some(binary) = text.strip_prefix('%') {
+      (binary, 2, 8)
+    } else if text.starts_with(|c: char| c.is_ascii_digit()) {
+      (text, 10, 3)
+    } else if is_identifier(text) {
+      return self.label(text);
+    } else {
+      return Err(syntax());
+    };
+
+    let value = u16::from_str_radix(digits, radix).map_err(|_| syntax())?;
+    Ok(Address {
+      value,
+      wide: digits.len() > narrow || value > 0xFF,
+    })
+  }
+
+  // Forward references are absolute wherever the instruction has that form,
+  // as their address is not known when the first pass sizes it. Until then they stand in as
+  // the current address, which keeps branches to them in range.
+  fn label(&self, label: &str) -> Result<Address, ErrorKind> {
+    match self.labels.get(label) {
+      Some(&(value, line)) => Ok(Address {
+        value,
+        wide: value > 0xFF || line > self.line,
+      }),
+      None if self.first_pass => Ok(Address {
+        value: self.pc(),
+        wide: true,
+      }),
+      None => Err(ErrorKind::UndefinedLabel(label.to_string())),
+    }
+  }
+}
+
+fn is_identifier(text: &str) -> bool {
+  let mut chars = text.chars();
+  match chars.next() {
+    Some(first) if first.is_ascii_alphabetic() || first == '_' => {
+      chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    }
+    _ => false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn forward_references_resolve() {
+    let assembly = assemble(
+      "
+      .org $0600
+      start: JMP end
+             BNE end
+             NOP
+      end:   RTS
+      ",
+    )
+    .unwrap();
+
+    assert_eq!(assembly.origin, 0x0600);
+    assert_eq!(assembly.bytes, [0x4C, 0x06, 0x06, 0xD0, 0x01, 0xEA, 0x60]);
+    assert_eq!(assembly.symbols["start"], 0x0600);
+    assert_eq!(assembly.symbols["end"], 0x0606);
+  }
+
+  #[test]
+  fn branches_wrap_around_the_address_space() {
+    let assembly = assemble(".org $FFF0\nBNE $0000").unwrap();
+    assert_eq!(assembly.bytes, [0xD0, 0x0E]);
+
+    let assembly = assemble(".org $0000\nBNE $FFF0").unwrap();
+    assert_eq!(assembly.bytes, [0xD0, 0xEE]);
+  }
+
+  #[test]
+  fn distant_branches_are_rejected() {
+    let error = assemble(".org $0600\nBNE $0700").unwrap_err();
+    assert_eq!(
+      error,
+      AsmError {
+        line: 2,
+        kind: ErrorKind::BranchOutOfRange(0xFE),
+      }
+    );
+
+    let error = assemble(".org $0600\nBNE $0500").unwrap_err();
+    assert_eq!(error.kind, ErrorKind::BranchOutOfRange(-0x102));
+  }
+
+  #[test]
+  fn labels_are_defined_once() {
+    let error = assemble("loop: NOP\nloop: NOP").unwrap_err();
+    assert_eq!(
+      error,
+      AsmError {
+        line: 2,
+        kind: ErrorKind::DuplicateLabel("loop".to_string()),
+      }
+    );
+  }
+
+  #[test]
+  fn only_backward_labels_use_zero_page() {
+    let assembly = assemble(
+      "
+      .org $0010
+      before: .byte 0
+              LDA before
+              LDA after
+      after:  .byte 0
+      ",
+    )
+    .unwrap();
+
+    // The forward reference was sized before its address was known.
+    assert_eq!(assembly.bytes, [0x00, 0xA5, 0x10, 0xAD, 0x16, 0x00, 0x00]);
+  }
+
+  #[test]
+  fn forward_labels_use_zero_page_without_an_absolute_form() {
+    let assembly = assemble(
+      "
+            STX data,Y
+            STY data,X
+            BRK
+      data: .byte 0
+      ",
+    )
+    .unwrap();
+    assert_eq!(assembly.bytes, [0x96, 0x05, 0x94, 0x05, 0x00, 0x00]);
+
+    let error = assemble(".org $0100\nSTX data,Y\ndata: .byte 0").unwrap_err();
+    assert_eq!(
+      error,
+      AsmError {
+        line: 2,
+        kind: ErrorKind::ValueOutOfRange(0x0102),
+      }
+    );
+  }
+
+  #[test]
+  fn wide_literals_force_absolute() {
+    let assembly = assemble("LDA $12\nLDA $0012").unwrap();
+    assert_eq!(assembly.bytes, [0xA5, 0x12, 0xAD, 0x12, 0x00]);
+  }
+
+  #[test]
+  fn symbol_table_lists_labels_by_address() {
+    let assembly =
+      assemble(".org $0600\nzeta: NOP\nalpha: NOP\nmid: RTS").unwrap();
+    assert_eq!(
+      assembly.symbol_table(),
+      "$0600 zeta\n$0601 alpha\n$0602 mid\n"
+    );
+  }
+}
@@ -0,0 +1,500 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryFrom;
+use std::io::{self, BufRead, Write};
+
+use sixtyfiveohtwo::bus::Bus;
+use sixtyfiveohtwo::cpu::Halt;
+use sixtyfiveohtwo::disasm::{disassemble, disassemble_one, Line};
+use sixtyfiveohtwo::instructions::JSR_OPCODE;
+use sixtyfiveohtwo::registers::Flags;
+use sixtyfiveohtwo::{trace, Cpu, Memory};
+
+use crate::{address, dump, number};
+
+const HELP: &str = "\
+Commands:
+  s, step [count]           Execute count instructions (default 1)
+  n, next                   Step, running a JSR until it returns
+  c, continue               Run until a breakpoint, watchpoint, BRK or halt
+  b, break <addr>           Stop when the PC reaches addr
+  w, watch <addr> [r|w|rw]  Stop once an instruction reads or writes addr
+  delete <addr>             Remove the breakpoint and watchpoint at addr
+  i, info                   List the breakpoints and watchpoints
+  r, registers              Show the registers
+  set <reg> <value>         Set pc, sp, a, x, y or p
+  x <from> [to]             Hex dump memory, sixteen bytes without to
+  d, disasm [addr] [count]  Disassemble around the PC, or from addr
+  h, help                   Show this help
+  q, quit                   Leave the debugger
+
+An empty line repeats the last command.";
+
+// Instructions shown before the PC when disassembling around it.
+const CONTEXT: usize = 3;
+// Default number of instructions to disassemble.
+const LISTING: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Watch {
+  read: bool,
+  write: bool,
+}
+
+// Memory that notes each access to a watched address, for the debugger to
+// stop on once the instruction has finished.
+#[derive(Default)]
+pub struct Watched {
+  memory: Memory,
+  watches: BTreeMap<u16, Watch>,
+  hits: Vec<String>,
+}
+
+impl Bus for Watched {
+  fn read(&mut self, addr: u16) -> u8 {
+    let value = self.memory.read(addr);
+    if self.watches.get(&addr).is_some_and(|watch| watch.read) {
+      self
+        .hits
+        .push(format!("read ${:02X} from ${:04X}", value, addr));
+    }
+    value
+  }
+
+  fn write(&mut self, addr: u16, value: u8) {
+    if self.watches.get(&addr).is_some_and(|watch| watch.write) {
+      self
+        .hits
+        .push(format!("write ${:02X} to ${:04X}", value, addr));
+    }
+    self.memory.write(addr, value);
+  }
+
+  fn peek(&self, addr: u16) -> u8 {
+    self.memory.peek(addr)
+  }
+}
+
+enum Flow {
+  Continue,
+  Quit,
+}
+
+// A command that cannot be carried out is reported and the session goes on,
+// while failing to write the output ends it.
+enum Error {
+  Command(String),
+  Io(io::Error),
+}
+
+impl From<String> for Error {
+  fn from(error: String) -> Self {
+    Error::Command(error)
+  }
+}
+
+impl From<io::Error> for Error {
+  fn from(error: io::Error) -> Self {
+    Error::Io(error)
+  }
+}
+
+struct Debugger<W: Write> {
+  cpu: Cpu<Watched>,
+  breakpoints: BTreeSet<u16>,
+  output: W,
+}
+
+// Read commands from `input` until `quit` or the end of input.
+pub fn debug<R: BufRead, W: Write>(
+  cpu: Cpu<Watched>,
+  mut input: R,
+  output: W,
+) -> io::Result<()> {
+  let mut debugger = Debugger {
+    cpu,
+    breakpoints: BTreeSet::new(),
+    output,
+  };
+  let mut last = String::new();
+
+  debugger.show_pc()?;
+  loop {
+    write!(debugger.output, "(6502) ")?;
+    debugger.output.flush()?;
+
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+      return writeln!(debugger.output);
+    }
+
+    let line = line.trim();
+    if !line.is_empty() {
+      last = line.to_string();
+    } else if last.is_empty() {
+      continue;
+    }
+
+    match debugger.command(&last) {
+      Ok(Flow::Continue) => {}
+      Ok(Flow::Quit) => return Ok(()),
+      Err(Error::Command(error)) => writeln!(debugger.output, "{}", error)?,
+      Err(Error::Io(error)) => return Err(error),
+    }
+  }
+}
+
+impl<W: Write> Debugger<W> {
+  fn command(&mut self, line: &str) -> Result<Flow, Error> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let (command, args) = (words[0], &words[1..]);
+
+    match (command, args) {
+      ("s", []) | ("step", []) => self.step(1)?,
+      ("s", [count]) | ("step", [count]) => match number(count)? {
+        0 => return Err(Error::Command("Step at least one".to_string())),
+        count => self.step(count)?,
+      },
+      ("n", []) | ("next", []) => self.next()?,
+      ("c", []) | ("continue", []) => self.resume()?,
+      ("b", [addr]) | ("break", [addr]) => {
+        self.breakpoints.insert(address(addr)?);
+      }
+      ("w", [addr]) | ("watch", [addr]) => self.watch(addr, "rw")?,
+      ("w", [addr, kind]) | ("watch", [addr, kind]) => {
+        self.watch(addr, kind)?
+      }
+      ("delete", [addr]) => self.delete(address(addr)?)?,
+      ("i", []) | ("info", []) => self.info()?,
+      ("r", []) | ("registers", []) => {
+        write!(self.output, "{}", self.cpu.registers)?
+      }
+      ("set", [register, value]) => self.set(register, value)?,
+      ("x", [from]) => {
+        let from = address(from)?;
+        dump(
+          &mut self.output,
+          &self.cpu.bus,
+          from,
+          from.saturating_add(15),
+        )?;
+      }
+      ("x", [from, to]) => {
+        let (from, to) = (address(from)?, address(to)?);
+        if from > to {
+          return Err(format!("${:04X} is after ${:04X}", from, to).into());
+        }
+        dump(&mut self.output, &self.cpu.bus, from, to)?;
+      }
+      ("d", []) | ("disasm", []) => self.disasm_around()?,
+      ("d", [addr]) | ("disasm", [addr]) => {
+        self.disasm(address(addr)?, LISTING)?
+      }
+      ("d", [addr, count]) | ("disasm", [addr, count]) => {
+        let count = usize::try_from(number(count)?)
+          .map_err(|_| format!("`{}` is too many lines", count))?;
+        self.disasm(address(addr)?, count)?
+      }
+      ("h", []) | ("help", []) => writeln!(self.output, "{}", HELP)?,
+      ("q", []) | ("quit", []) => return Ok(Flow::Quit),
+      _ => {
+        return Err(Error::Command(format!(
+          "Cannot understand `{}`, type `help` for commands",
+          line
+        )))
+      }
+    }
+
+    Ok(Flow::Continue)
+  }
+
+  // Execute a single instruction, giving the reason to stop early if there
+  // is one.
+  fn execute(&mut self) -> Option<String> {
+    if let Some(halt) = self.halted() {
+      return Some(halt);
+    }
+
+    let pc = self.cpu.registers.pc.value;
+    self.cpu.bus.hits.clear();
+    if let Err(error) = self.cpu.step() {
+      return Some(error.to_string());
+    }
+
+    if !self.cpu.bus.hits.is_empty() {
+      let hits = self.cpu.bus.hits.join(", ");
+      return Some(format!("Watchpoint: {} at ${:04X}", hits, pc));
+    }
+    self.halted()
+  }
+
+  fn halted(&self) -> Option<String> {
+    match self.cpu.halt {
+      Halt::Running => None,
+      Halt::Waiting => Some("Waiting for an interrupt after WAI".to_string()),
+      Halt::Stopped => Some("Stopped by STP".to_string()),
+    }
+  }
+
+  // Execute instructions until `done`, or a reason to stop. Breakpoints are
+  // only checked after the first instruction, so running from one moves on.
+  fn run<F: FnMut(&Cpu<Watched>) -> bool>(
+    &mut self,
+    mut done: F,
+  ) -> Option<String> {
+    loop {
+      if let Some(reason) = self.execute() {
+        return Some(reason);
+      }
+      if done(&self.cpu) {
+        return None;
+      }
+
+      let pc = self.cpu.registers.pc.value;
+      if self.breakpoints.contains(&pc) {
+        return Some(format!("Breakpoint at ${:04X}", pc));
+      }
+    }
+  }
+
+  fn step(&mut self, count: u64) -> io::Result<()> {
+    let mut left = count;
+    let stop = self.run(|_| {
+      left = left.saturating_sub(1);
+      left == 0
+    });
+    self.stopped(stop)
+  }
+
+  // Step over a JSR by running until it returns to the next instruction,
+  // with the stack back where it was so recursion is not mistaken for it.
+  fn next(&mut self) -> io::Result<()> {
+    let pc = self.cpu.registers.pc.value;
+    if self.cpu.bus.peek(pc) != JSR_OPCODE {
+      return self.step(1);
+    }
+
+    let sp = self.cpu.registers.sp.value;
+    let back = pc.wrapping_add(3);
+    let stop = self.run(|cpu| {
+      cpu.registers.pc.value == back && cpu.registers.sp.value == sp
+    });
+    self.stopped(stop)
+  }
+
+  fn resume(&mut self) -> io::Result<()> {
+    let stop = self.run(|cpu| cpu.bus.peek(cpu.registers.pc.value) == 0x00);
+    let pc = self.cpu.registers.pc.value;
+    self.stopped(stop.or_else(|| Some(format!("BRK at ${:04X}", pc))))
+  }
+
+  fn stopped(&mut self, reason: Option<String>) -> io::Result<()> {
+    if let Some(reason) = reason {
+      writeln!(self.output, "Stopped: {}", reason)?;
+    }
+    self.show_pc()
+  }
+
+  // The instruction about to execute, with the registers.
+  fn show_pc(&mut self) -> io::Result<()> {
+    writeln!(
+      self.output,
+      "{}",
+      trace::line(
+        &self.cpu.bus,
+        &self.cpu.registers,
+        self.cpu.cycles,
+        self.cpu.variant,
+        self.cpu.undocumented_opcodes,
+      )
+    )
+  }
+
+  fn watch(&mut self, addr: &str, kind: &str) -> Result<(), String> {
+    let watch = match kind {
+      "r" => Watch {
+        read: true,
+        write: false,
+      },
+      "w" => Watch {
+        read: false,
+        write: true,
+      },
+      "rw" => Watch {
+        read: true,
+        write: true,
+      },
+      _ => return Err(format!("Watch `{}`, not r, w or rw", kind)),
+    };
+    self.cpu.bus.watches.insert(address(addr)?, watch);
+    Ok(())
+  }
+
+  fn delete(&mut self, addr: u16) -> Result<(), String> {
+    let breakpoint = self.breakpoints.remove(&addr);
+    let watchpoint = self.cpu.bus.watches.remove(&addr).is_some();
+    if breakpoint || watchpoint {
+      Ok(())
+    } else {
+      Err(format!("Nothing set at ${:04X}", addr))
+    }
+  }
+
+  fn info(&mut self) -> io::Result<()> {
+    if self.breakpoints.is_empty() && self.cpu.bus.watches.is_empty() {
+      writeln!(self.output, "No breakpoints or watchpoints")?;
+    }
+    for addr in &self.breakpoints {
+      writeln!(self.output, "Breakpoint ${:04X}", addr)?;
+    }
+    for (addr, watch) in &self.cpu.bus.watches {
+      let kind = match (watch.read, watch.write) {
+        (true, true) => "rw",
+        (true, false) => "r",
+        _ => "w",
+      };
+      writeln!(self.output, "Watchpoint ${:04X} {}", addr, kind)?;
+    }
+    Ok(())
+  }
+
+  fn set(&mut self, register: &str, value: &str) -> Result<(), Error> {
+    let byte = || {
+      u8::try_from(number(value)?)
+        .map_err(|_| format!("`{}` does not fit in a byte", value))
+    };
+
+    let registers = &mut self.cpu.registers;
+    match register.to_lowercase().as_str() {
+      "pc" => registers.pc.value = address(value)?,
+      "sp" => registers.sp.value = byte()?,
+      "a" => registers.acc.value = byte()?,
+      "x" => registers.x.value = byte()?,
+      "y" => registers.y.value = byte()?,
+      "p" => registers.flags = Flags::from_byte(byte()?),
+      _ => return Err(format!("Unknown register `{}`", register).into()),
+    }
+
+    Ok(self.show_pc()?)
+  }
+
+  fn disasm(&mut self, from: u16, count: usize) -> io::Result<()> {
+    let mut pc = from;
+    for _ in 0..count {
+      let line = self.disassemble_one(pc);
+      pc = pc.wrapping_add(line.bytes.len() as u16);
+      self.show_line(&line)?;
+    }
+    Ok(())
+  }
+
+  // Code cannot be decoded backwards, so the instructions before the PC come
+  // from disassembling forwards from the furthest address that lines up with
+  // it.
+  fn disasm_around(&mut self) -> io::Result<()> {
+    let pc = self.cpu.registers.pc.value;
+    let furthest = (CONTEXT * 3) as u16;
+
+    let before = (1..=furthest.min(pc))
+      .rev()
+      .map(|back| {
+        disassemble(
+          &self.cpu.bus,
+          pc - back,
+          pc - 1,
+          self.cpu.variant,
+          self.cpu.undocumented_opcodes,
+        )
+      })
+      .find(|lines| {
+        lines.last().is_some_and(|line| {
+          u32::from(line.address) + line.bytes.len() as u32 == u32::from(pc)
+        })
+      })
+      .unwrap_or_default();
+
+    for line in before.iter().skip(before.len().saturating_sub(CONTEXT)) {
+      self.show_line(line)?;
+    }
+    self.disasm(pc, LISTING - CONTEXT)
+  }
+
+  fn disassemble_one(&self, pc: u16) -> Line {
+    disassemble_one(
+      &self.cpu.bus,
+      pc,
+      self.cpu.variant,
+      self.cpu.undocumented_opcodes,
+    )
+  }
+
+  // Marks the PC with `>` and breakpoints with `*`.
+  fn show_line(&mut self, line: &Line) -> io::Result<()> {
+    let pc = if line.address == self.cpu.registers.pc.value {
+      '>'
+    } else {
+      ' '
+    };
+    let breakpoint = if self.breakpoints.contains(&line.address) {
+      '*'
+    } else {
+      ' '
+    };
+    writeln!(self.output, "{}{} {}", pc, breakpoint, line)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // Run a session over the program at $0200, returning what was printed
+  // with the prompts left out.
+  fn session(program: &[u8], commands: &str) -> String {
+    let mut cpu = Cpu::with_bus(Watched::default());
+    cpu.load(0x0200, program);
+    cpu.registers.pc.value = 0x0200;
+
+    let mut output = Vec::new();
+    debug(cpu, commands.as_bytes(), &mut output).unwrap();
+    String::from_utf8(output).unwrap().replace("(6502) ", "")
+  }
+
+  // LDX #3, loop: DEX, BNE loop, STA $10, BRK
+  const COUNTDOWN: [u8; 9] =
+    [0xA2, 0x03, 0xCA, 0xD0, 0xFD, 0x8D, 0x10, 0x00, 0x00];
+
+  #[test]
+  fn stops_at_breakpoints_and_watchpoints() {
+    let output = session(
+      &COUNTDOWN,
+      "break $0205\ncontinue\nwatch $10 w\ncontinue\nx $10\nquit\n",
+    );
+
+    let stops: Vec<&str> = output
+      .lines()
+      .filter(|line| line.contains("Stopped"))
+      .collect();
+    assert_eq!(
+      stops,
+      [
+        "Stopped: Breakpoint at $0205",
+        "Stopped: Watchpoint: write $00 to $0010 at $0205",
+      ]
+    );
+    assert!(output.contains("\n0010  00 00"));
+  }
+
+  #[test]
+  fn empty_lines_repeat_the_last_command() {
+    let output = session(&COUNTDOWN, "step\n\n\ninfo\n");
+    assert!(output.contains("\n0203 "), "{}", output);
+    assert!(output.contains("No breakpoints or watchpoints"));
+  }
+
+  #[test]
+  fn step_needs_a_count_of_at_least_one() {
+    // Nothing runs, so the next step is still the first instruction.
+    let output = session(&COUNTDOWN, "step 0\nstep\n");
+    assert!(output.contains("Step at least one\n0202 "), "{}", output);
+  }
+}
@@ -0,0 +1,298 @@
+use std::convert::TryFrom;
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::process::ExitCode;
+
+use sixtyfiveohtwo::bus::Bus;
+use sixtyfiveohtwo::cpu::Halt;
+use sixtyfiveohtwo::instructions::interp::Variant;
+use sixtyfiveohtwo::Cpu;
+
+mod debugger;
+
+const USAGE: &str = "\
+Usage: sixtyfiveohtwo run <binary> [options]
+       sixtyfiveohtwo debug <binary> [options]
+
+Run executes the binary until it stops, debug loads it and waits for
+commands, type `help` for a list. Debug takes all but the --until,
+--cycles and --dump options.
+
+Options:
+  --origin <addr>     Load the binary at addr (default $0000)
+  --start <addr>      Start executing at addr (default the origin)
+  --reset             Start from the reset vector instead
+  --until <addr>      Stop when the PC reaches addr
+  --cycles <count>    Stop once count cycles have run
+  --variant <name>    nmos6502, ricoh2a03 or wdc65c02 (default nmos6502)
+  --undocumented      Decode the stable undocumented NMOS opcodes
+  --dump <from>-<to>  Print memory from..=to when stopped, may be repeated
+  --trace <file>      Log each instruction in nestest format, - for stdout
+
+Run always stops before a BRK. Numbers are hex with a $ or 0x
+prefix, or decimal.";
+
+enum Command {
+  Run,
+  Debug,
+}
+
+struct RunOptions {
+  binary: String,
+  origin: u16,
+  start: Option<u16>,
+  reset: bool,
+  until: Option<u16>,
+  cycles: Option<u64>,
+  variant: Variant,
+  undocumented: bool,
+  dumps: Vec<(u16, u16)>,
+  trace: Option<String>,
+}
+
+fn main() -> ExitCode {
+  let args: Vec<String> = env::args().skip(1).collect();
+
+  let parsed = match args.first().map(String::as_str) {
+    Some("run") => parse_run(&args[1..]).map(|options| (Command::Run, options)),
+    Some("debug") => {
+      parse_debug(&args[1..]).map(|options| (Command::Debug, options))
+    }
+    Some("help") | Some("--help") | Some("-h") => {
+      println!("{}", USAGE);
+      return ExitCode::SUCCESS;
+    }
+    Some(command) => Err(format!("Unknown command `{}`", command)),
+    None => Err("No command given".to_string()),
+  };
+
+  let (command, options) = match parsed {
+    Ok(parsed) => parsed,
+    Err(error) => {
+      eprintln!("{}\n\n{}", error, USAGE);
+      return ExitCode::from(2);
+    }
+  };
+
+  let result = match command {
+    Command::Run => run(&options),
+    Command::Debug => debug(&options),
+  };
+  match result {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(error) => {
+      eprintln!("{}", error);
+      ExitCode::FAILURE
+    }
+  }
+}
+
+fn parse_run(args: &[String]) -> Result<RunOptions, String> {
+  let mut args = args.iter();
+  let mut options = RunOptions {
+    binary: String::new(),
+    origin: 0,
+    start: None,
+    reset: false,
+    until: None,
+    cycles: None,
+    variant: Variant::default(),
+    undocumented: false,
+    dumps: Vec::new(),
+    trace: None,
+  };
+
+  while let Some(arg) = args.next() {
+    let mut value = || {
+      args
+        .next()
+        .map(String::as_str)
+        .ok_or_else(|| format!("{} needs a value", arg))
+    };
+
+    match arg.as_str() {
+      "--origin" => options.origin = address(value()?)?,
+      "--start" => options.start = Some(address(value()?)?),
+      "--reset" => options.reset = true,
+      "--until" => options.until = Some(address(value()?)?),
+      "--cycles" => options.cycles = Some(number(value()?)?),
+      "--variant" => options.variant = variant(value()?)?,
+      "--undocumented" => options.undocumented = true,
+      "--dump" => options.dumps.push(range(value()?)?),
+      "--trace" => options.trace = Some(value()?.to_string()),
+      flag if flag.starts_with("--") => {
+        return Err(format!("Unknown option `{}`", flag))
+      }
+      binary if options.binary.is_empty() => {
+        options.binary = binary.to_string()
+      }
+      extra => return Err(format!("Unexpected argument `{}`", extra)),
+    }
+  }
+
+  if options.binary.is_empty() {
+    return Err("No binary given".to_string());
+  }
+  if options.reset && options.start.is_some() {
+    return Err("--start and --reset cannot be used together".to_string());
+  }
+
+  Ok(options)
+}
+
+fn parse_debug(args: &[String]) -> Result<RunOptions, String> {
+  let options = parse_run(args)?;
+  let run_only = [
+    ("--until", options.until.is_some()),
+    ("--cycles", options.cycles.is_some()),
+    ("--dump", !options.dumps.is_empty()),
+  ];
+  match run_only.iter().find(|(_, given)| *given) {
+    Some((flag, _)) => Err(format!("{} only applies to run", flag)),
+    None => Ok(options),
+  }
+}
+
+fn number(text: &str) -> Result<u64, String> {
+  let parsed = if let Some(hex) = text.strip_prefix('$') {
+    u64::from_str_radix(hex, 16)
+  } else if let Some(hex) = text.strip_prefix("0x") {
+    u64::from_str_radix(hex, 16)
+  } else {
+    text.parse()
+  };
+
+  parsed.map_err(|_| format!("`{}` is not a number", text))
+}
+
+fn address(text: &str) -> Result<u16, String> {
+  let value = number(text)?;
+  u16::try_from(value).map_err(|_| format!("`{}` is not an address", text))
+}
+
+fn range(text: &str) -> Result<(u16, u16), String> {
+  let (from, to) = text
+    .split_once('-')
+    .ok_or_else(|| format!("`{}` is not a range like $0200-$02FF", text))?;
+  let (from, to) = (address(from)?, address(to)?);
+  if from > to {
+    return Err(format!("`{}` ends before it starts", text));
+  }
+
+  Ok((from, to))
+}
+
+fn variant(text: &str) -> Result<Variant, String> {
+  match text.to_lowercase().as_str() {
+    "nmos6502" | "6502" => Ok(Variant::Nmos6502),
+    "ricoh2a03" | "2a03" => Ok(Variant::Ricoh2A03),
+    "wdc65c02" | "65c02" => Ok(Variant::Wdc65C02),
+    _ => Err(format!("Unknown variant `{}`", text)),
+  }
+}
+
+// Set up the CPU as the options ask, with the binary loaded.
+fn load<B: Bus>(cpu: &mut Cpu<B>, options: &RunOptions) -> Result<(), String> {
+  let binary = fs::read(&options.binary)
+    .map_err(|error| format!("Cannot read {}: {}", options.binary, error))?;
+  if usize::from(options.origin) + binary.len() > 0x10000 {
+    return Err(format!(
+      "{} bytes do not fit at ${:04X}",
+      binary.len(),
+      options.origin
+    ));
+  }
+
+  cpu.variant = options.variant;
+  cpu.undocumented_opcodes = options.undocumented;
+  cpu.load(options.origin, &binary);
+  cpu.tracer = match options.trace.as_deref() {
+    Some("-") => Some(Box::new(io::stdout())),
+    Some(path) => Some(Box::new(io::BufWriter::new(
+      fs::File::create(path)
+        .map_err(|error| format!("Cannot create {}: {}", path, error))?,
+    ))),
+    None => None,
+  };
+  if options.reset {
+    cpu.reset();
+  } else {
+    cpu.registers.pc.value = options.start.unwrap_or(options.origin);
+  }
+
+  Ok(())
+}
+
+fn run(options: &RunOptions) -> Result<(), String> {
+  let mut cpu = Cpu::new();
+  load(&mut cpu, options)?;
+
+  let result = loop {
+    let pc = cpu.registers.pc.value;
+    if cpu.bus.peek(pc) == 0x00 {
+      break Ok(format!("BRK at ${:04X}", pc));
+    }
+    if options.until == Some(pc) {
+      break Ok(format!("PC ${:04X}", pc));
+    }
+    if options.cycles.is_some_and(|limit| cpu.cycles >= limit) {
+      break Ok(format!("cycle limit at ${:04X}", pc));
+    }
+    if cpu.halt != Halt::Running {
+      break Ok(format!("{:?} at ${:04X}", cpu.halt, pc));
+    }
+    if let Err(error) = cpu.step() {
+      break Err(error.to_string());
+    }
+  };
+
+  report(&mut io::stdout().lock(), &cpu, &result, &options.dumps)
+    .map_err(|error| error.to_string())?;
+  result.map(|_| ())
+}
+
+// Why the run stopped, the final state and any memory asked for.
+fn report<W: Write>(
+  output: &mut W,
+  cpu: &Cpu,
+  result: &Result<String, String>,
+  dumps: &[(u16, u16)],
+) -> io::Result<()> {
+  if let Ok(reason) = result {
+    writeln!(output, "Stopped: {}", reason)?;
+  }
+  write!(output, "{}", cpu.registers)?;
+  writeln!(output, "Cycles: {}", cpu.cycles)?;
+  for &(from, to) in dumps {
+    dump(output, &cpu.bus, from, to)?;
+  }
+  Ok(())
+}
+
+fn debug(options: &RunOptions) -> Result<(), String> {
+  let mut cpu = Cpu::with_bus(debugger::Watched::default());
+  load(&mut cpu, options)?;
+  let stdin = io::stdin();
+  debugger::debug(cpu, stdin.lock(), io::stdout())
+    .map_err(|error| error.to_string())
+}
+
+// Hex dump, sixteen bytes to a row.
+fn dump<W: Write, B: Bus>(
+  output: &mut W,
+  bus: &B,
+  from: u16,
+  to: u16,
+) -> io::Result<()> {
+  let mut row = u32::from(from);
+  while row <= u32::from(to) {
+    let end = (row + 15).min(u32::from(to));
+    let bytes: Vec<String> = (row..=end)
+      .map(|addr| format!("{:02X}", bus.peek(addr as u16)))
+      .collect();
+    writeln!(output, "{:04X}  {}", row, bytes.join(" "))?;
+    row += 16;
+  }
+  Ok(())
+}
@@ -0,0 +1,154 @@
+use crate::registers::{IndexRegister, IndexX, IndexY};
+
+pub trait Bus {
+  fn read(&mut self, addr: u16) -> u8;
+
+  fn write(&mut self, addr: u16, value: u8);
+
+  // Read without triggering any device side effects, for inspection.
+  fn peek(&self, addr: u16) -> u8;
+
+  // Accesses the CPU makes only because of how it is wired, like reading the
+  // unfixed address while an index carries into the next page, or writing
+  // back the unmodified value of a read-modify-write. Nothing is done with
+  // them unless the bus is wrapped in `CycleAccurate`.
+  fn dummy_read(&mut self, _addr: u16) {}
+
+  fn dummy_write(&mut self, _addr: u16, _value: u8) {}
+
+  // An operand byte the CPU only fetches after other accesses that may have
+  // overwritten it, like the high byte of JSR, read after the pushes. Only a
+  // cycle accurate bus reads it again, otherwise the decoded byte stands.
+  fn deferred_read(&mut self, _addr: u16) -> Option<u8> {
+    None
+  }
+
+  fn zero_page(&mut self, addr: u8) -> u8 {
+    self.read(u16::from(addr))
+  }
+
+  fn zero_page_write(&mut self, addr: u8, value: u8) {
+    self.write(u16::from(addr), value);
+  }
+
+  fn zero_page_register<T: IndexRegister>(
+    &mut self,
+    addr: u8,
+    register: &T,
+  ) -> u8 {
+    self.zero_page(addr.wrapping_add(register.read()))
+  }
+
+  fn zero_page_register_write<T: IndexRegister>(
+    &mut self,
+    addr: u8,
+    register: &T,
+    value: u8,
+  ) {
+    self.zero_page_write(addr.wrapping_add(register.read()), value);
+  }
+
+  fn absolute(&mut self, addr: u16) -> u8 {
+    self.read(addr)
+  }
+
+  fn absolute_write(&mut self, addr: u16, value: u8) {
+    self.write(addr, value);
+  }
+
+  fn absolute_register<T: IndexRegister>(
+    &mut self,
+    addr: u16,
+    register: &T,
+  ) -> u8 {
+    self.absolute(addr.wrapping_add(u16::from(register.read())))
+  }
+
+  fn absolute_register_write<T: IndexRegister>(
+    &mut self,
+    addr: u16,
+    register: &T,
+    value: u8,
+  ) {
+    self.absolute_write(addr.wrapping_add(u16::from(register.read())), value);
+  }
+
+  // NOTE: u16 because read jump location from memory
+  fn indirect(&mut self, addr: u16) -> u16 {
+    let first = self.absolute(addr);
+
+    // The high byte is fetched without carrying into the page, so a pointer
+    // at $xxFF reads its high byte from $xx00.
+    let [low, high] = addr.to_le_bytes();
+    let second = self.absolute(u16::from_le_bytes([low.wrapping_add(1), high]));
+
+    u16::from_le_bytes([first, second])
+  }
+
+  fn absolute_pointer(&mut self, addr: u16) -> u16 {
+    u16::from_le_bytes([
+      self.absolute(addr),
+      self.absolute(addr.wrapping_add(1)),
+    ])
+  }
+
+  // Pointer stored in the zero page, wrapping within it.
+  fn zero_page_pointer(&mut self, addr: u8) -> u16 {
+    u16::from_le_bytes([
+      self.zero_page(addr),
+      self.zero_page(addr.wrapping_add(1)),
+    ])
+  }
+
+  fn indexed_indirect(&mut self, addr: u8, register: &IndexX) -> u8 {
+    let pointer = self.zero_page_pointer(addr.wrapping_add(register.read()));
+    self.absolute(pointer)
+  }
+
+  fn indexed_indirect_write(&mut self, addr: u8, register: &IndexX, value: u8) {
+    let pointer = self.zero_page_pointer(addr.wrapping_add(register.read()));
+    self.absolute_write(pointer, value);
+  }
+
+  fn indirect_indexed(&mut self, addr: u8, register: &IndexY) -> u8 {
+    let pointer = self.zero_page_pointer(addr);
+    self.absolute_register(pointer, register)
+  }
+
+  fn indirect_indexed_write(&mut self, addr: u8, register: &IndexY, value: u8) {
+    let pointer = self.zero_page_pointer(addr);
+    self.absolute_register_write(pointer, register, value);
+  }
+}
+
+// Passes dummy accesses on as real reads and writes, so the wrapped bus sees
+// one access for every cycle an instruction takes. The pattern is that of the
+// NMOS parts, for every variant: the 65C02 differs in places, e.g. it reads
+// the operand of a read-modify-write twice instead of writing it back.
+pub struct CycleAccurate<'a, B: Bus>(pub &'a mut B);
+
+impl<'a, B: Bus> Bus for CycleAccurate<'a, B> {
+  fn read(&mut self, addr: u16) -> u8 {
+    self.0.read(addr)
+  }
+
+  fn write(&mut self, addr: u16, value: u8) {
+    self.0.write(addr, value)
+  }
+
+  fn peek(&self, addr: u16) -> u8 {
+    self.0.peek(addr)
+  }
+
+  fn dummy_read(&mut self, addr: u16) {
+    self.0.read(addr);
+  }
+
+  fn dummy_write(&mut self, addr: u16, value: u8) {
+    self.0.write(addr, value)
+  }
+
+  fn deferred_read(&mut self, addr: u16) -> Option<u8> {
+    Some(self.0.read(addr))
+  }
+}
@@ -0,0 +1,506 @@
+use std::io;
+
+use crate::bus::{Bus, CycleAccurate};
+use crate::instructions::cmos::{single_cycle_nop, STP_OPCODE, WAI_OPCODE};
+use crate::instructions::interp::{interp, DecodeError, Variant};
+use crate::instructions::{
+  interrupt, IRQ_VECTOR, JSR_OPCODE, NMI_VECTOR, RESET_VECTOR,
+};
+use crate::memory::Memory;
+use crate::registers::Registers;
+use crate::trace;
+
+pub type Trap<B> = Box<dyn FnMut(&mut B, &mut Registers, DecodeError)>;
+
+// What the CPU does when it meets an opcode it cannot decode.
+pub enum IllegalOpcodePolicy<B> {
+  // Stop at the opcode, returning the error from every step.
+  Halt,
+  // Hand over to the embedder, who decides how execution continues.
+  Trap(Trap<B>),
+  // Skip the opcode as a one byte, two cycle NOP.
+  Nop,
+}
+
+// Whether the clock is running, or stopped by a 65C02 WAI or STP.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Halt {
+  Running,
+  // Resumes on any interrupt, even a masked IRQ.
+  Waiting,
+  // Resumes only on reset.
+  Stopped,
+}
+
+pub struct Cpu<B: Bus = Memory> {
+  pub bus: B,
+  pub registers: Registers,
+  pub cycles: u64,
+  pub variant: Variant,
+  // Decode the stable undocumented NMOS opcodes instead of treating them as
+  // illegal.
+  pub undocumented_opcodes: bool,
+  pub illegal_opcodes: IllegalOpcodePolicy<B>,
+  pub halt: Halt,
+  // Receives a line per instruction, in the nestest log format.
+  pub tracer: Option<Box<dyn io::Write>>,
+  // Make every bus access the hardware makes, one per cycle: the opcode and
+  // operand fetches, and the dummy reads and writes in between. Only the
+  // NMOS timing is modelled, so a 65C02 makes the accesses of an NMOS part.
+  // Otherwise instructions are decoded with `peek` and only access the bus
+  // for their operands.
+  pub cycle_accurate: bool,
+  nmi_pending: bool,
+  irq_line: bool,
+}
+
+impl Cpu {
+  pub fn new() -> Self {
+    Cpu::with_bus(Memory::new())
+  }
+}
+
+impl Default for Cpu {
+  fn default() -> Self {
+    Cpu::new()
+  }
+}
+
+impl<B: Bus> Cpu<B> {
+  pub fn with_bus(bus: B) -> Self {
+    Cpu {
+      bus,
+      registers: Registers::default(),
+      cycles: 0,
+      variant: Variant::default(),
+      undocumented_opcodes: false,
+      illegal_opcodes: IllegalOpcodePolicy::Halt,
+      halt: Halt::Running,
+      tracer: None,
+      cycle_accurate: false,
+      nmi_pending: false,
+      irq_line: false,
+    }
+  }
+
+  // Load the program counter from the reset vector, as on power up.
+  pub fn reset(&mut self) {
+    self.registers.pc.value = u16::from_le_bytes([
+      self.bus.read(RESET_VECTOR),
+      self.bus.read(RESET_VECTOR.wrapping_add(1)),
+    ]);
+    self.registers.sp.value = 0xFD;
+    self.registers.flags.interrupt_disable = true;
+    self.nmi_pending = false;
+    self.halt = Halt::Running;
+    self.cycles += 7;
+  }
+
+  // NMI is edge triggered, so each call is serviced exactly once.
+  pub fn nmi(&mut self) {
+    self.nmi_pending = true;
+  }
+
+  // IRQ is level triggered, and serviced for as long as the line is held and
+  // interrupts are not disabled.
+  pub fn irq(&mut self, asserted: bool) {
+    self.irq_line = asserted;
+  }
+
+  pub fn load(&mut self, origin: u16, program: &[u8]) {
+    for (offset, byte) in program.iter().enumerate() {
+      self.bus.write(origin.wrapping_add(offset as u16), *byte);
+    }
+  }
+
+  // Service a pending interrupt, or else fetch, decode and execute the
+  // instruction at the program counter.
+  pub fn step(&mut self) -> Result<(), DecodeError> {
+    if self.halt == Halt::Stopped {
+      return Ok(());
+    }
+    if self.nmi_pending {
+      self.nmi_pending = false;
+      self.halt = Halt::Running;
+      self.service(NMI_VECTOR);
+      return Ok(());
+    }
+    if self.irq_line && !self.registers.flags.interrupt_disable {
+      self.halt = Halt::Running;
+      self.service(IRQ_VECTOR);
+      return Ok(());
+    }
+    if self.halt == Halt::Waiting {
+      // A masked IRQ ends the wait and execution carries on in line.
+      if !self.irq_line {
+        self.cycles += 1;
+        return Ok(());
+      }
+      self.halt = Halt::Running;
+    }
+
+    if let Some(tracer) = &mut self.tracer {
+      let line = trace::line(
+        &self.bus,
+        &self.registers,
+        self.cycles,
+        self.variant,
+        self.undocumented_opcodes,
+      );
+      // Tracing is best effort, and never stops execution.
+      let _ = writeln!(tracer, "{}", line);
+    }
+
+    let opcode = self.bus.peek(self.registers.pc.value);
+    let result = if self.cycle_accurate {
+      execute(
+        &mut CycleAccurate(&mut self.bus),
+        &mut self.registers,
+        self.variant,
+        self.undocumented_opcodes,
+        true,
+      )
+    } else {
+      execute(
+        &mut self.bus,
+        &mut self.registers,
+        self.variant,
+        self.undocumented_opcodes,
+        false,
+      )
+    };
+    let cycles = match result {
+      Ok(cycles) => cycles,
+      Err(error) => return self.illegal_opcode(error),
+    };
+    self.cycles += u64::from(cycles);
+
+    if self.variant == Variant::Wdc65C02 {
+      match opcode {
+        WAI_OPCODE => self.halt = Halt::Waiting,
+        STP_OPCODE => self.halt = Halt::Stopped,
+        _ => {}
+      }
+    }
+
+    Ok(())
+  }
+
+  // Hardware interrupts read the opcode they displace twice before pushing
+  // the return address. The 65C02 also leaves decimal mode.
+  fn service(&mut self, vector: u16) {
+    if self.cycle_accurate {
+      let pc = self.registers.pc.value;
+      self.bus.read(pc);
+      self.bus.read(pc);
+    }
+    interrupt(&mut self.bus, &mut self.registers, vector, false);
+    if self.variant == Variant::Wdc65C02 {
+      self.registers.flags.decimal_mode = false;
+    }
+    self.cycles += 7;
+  }
+
+  fn illegal_opcode(&mut self, error: DecodeError) -> Result<(), DecodeError> {
+    match &mut self.illegal_opcodes {
+      IllegalOpcodePolicy::Halt => return Err(error),
+      IllegalOpcodePolicy::Trap(trap) => {
+        trap(&mut self.bus, &mut self.registers, error)
+      }
+      IllegalOpcodePolicy::Nop => {
+        if self.cycle_accurate {
+          let pc = self.registers.pc.value;
+          self.bus.read(pc);
+          self.bus.read(pc.wrapping_add(1));
+        }
+        self.registers.pc.value = self.registers.pc.value.wrapping_add(1);
+        self.cycles += 2;
+      }
+    }
+
+    Ok(())
+  }
+
+  pub fn run_until(&mut self, pc: u16) -> Result<(), DecodeError> {
+    // A halted CPU would never get there, so stop waiting for it.
+    while self.registers.pc.value != pc && self.halt == Halt::Running {
+      self.step()?;
+    }
+
+    Ok(())
+  }
+
+  pub fn run_for(&mut self, instructions: usize) -> Result<(), DecodeError> {
+    for _ in 0..instructions {
+      self.step()?;
+    }
+
+    Ok(())
+  }
+}
+
+// Decode and evaluate the instruction at the program counter. With `fetch`
+// the opcode and operand are read over the bus too, along with the byte after
+// a one byte instruction, which the hardware reads and throws away.
+fn execute<B: Bus>(
+  bus: &mut B,
+  registers: &mut Registers,
+  variant: Variant,
+  undocumented_opcodes: bool,
+  fetch: bool,
+) -> Result<u8, DecodeError> {
+  let pc = registers.pc.value;
+  let instruction = interp(bus, pc, variant, undocumented_opcodes)?;
+
+  if fetch {
+    let opcode = bus.read(pc);
+    // Only the single cycle NOPs of the 65C02 are done before the next byte.
+    if variant != Variant::Wdc65C02 || !single_cycle_nop(opcode) {
+      bus.read(pc.wrapping_add(1));
+    }
+    // JSR reads its high byte last, once the return address is pushed.
+    if instruction.render().len() == 3 && opcode != JSR_OPCODE {
+      bus.read(pc.wrapping_add(2));
+    }
+  }
+
+  Ok(instruction.evaluate(bus, registers))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+  enum Cycle {
+    Read(u16),
+    Write(u16, u8),
+  }
+  use Cycle::*;
+
+  // Memory that records every access the CPU makes.
+  struct Log {
+    memory: Memory,
+    cycles: Vec<Cycle>,
+  }
+
+  impl Bus for Log {
+    fn read(&mut self, addr: u16) -> u8 {
+      self.cycles.push(Read(addr));
+      self.memory.read(addr)
+    }
+
+    fn write(&mut self, addr: u16, value: u8) {
+      self.cycles.push(Write(addr, value));
+      self.memory.write(addr, value)
+    }
+
+    fn peek(&self, addr: u16) -> u8 {
+      self.memory.peek(addr)
+    }
+  }
+
+  const ORIGIN: u16 = 0x0200;
+
+  fn cpu(program: &[u8]) -> Cpu<Log> {
+    let mut cpu = Cpu::with_bus(Log {
+      memory: Memory::new(),
+      cycles: Vec::new(),
+    });
+    cpu.cycle_accurate = true;
+    cpu.load(ORIGIN, program);
+    cpu.registers.pc.value = ORIGIN;
+    cpu.registers.sp.value = 0xFD;
+    cpu
+  }
+
+  // The accesses made by the next step, checked against its cycle count.
+  fn step(cpu: &mut Cpu<Log>) -> Vec<Cycle> {
+    cpu.bus.cycles.clear();
+    let before = cpu.cycles;
+    cpu.step().unwrap();
+    let cycles = std::mem::take(&mut cpu.bus.cycles);
+    assert_eq!(cycles.len() as u64, cpu.cycles - before);
+    cycles
+  }
+
+  #[test]
+  fn indexed_reads_touch_the_unfixed_address_across_a_page() {
+    // LDA $12F8,X
+    let mut cpu = cpu(&[0xBD, 0xF8, 0x12]);
+    cpu.registers.x.value = 0x01;
+    assert_eq!(
+      step(&mut cpu),
+      [Read(0x0200), Read(0x0201), Read(0x0202), Read(0x12F9)]
+    );
+
+    cpu.registers.pc.value = ORIGIN;
+    cpu.registers.x.value = 0x10;
+    assert_eq!(
+      step(&mut cpu),
+      [
+        Read(0x0200),
+        Read(0x0201),
+        Read(0x0202),
+        Read(0x1208),
+        Read(0x1308)
+      ]
+    );
+  }
+
+  #[test]
+  fn indexed_stores_always_touch_the_unfixed_address() {
+    // STA ($10),Y
+    let mut cpu = cpu(&[0x91, 0x10]);
+    cpu.bus.memory.write(0x0010, 0x00);
+    cpu.bus.memory.write(0x0011, 0x13);
+    cpu.registers.y.value = 0x05;
+    cpu.registers.acc.value = 0xAA;
+    assert_eq!(
+      step(&mut cpu),
+      [
+        Read(0x0200),
+        Read(0x0201),
+        Read(0x0010),
+        Read(0x0011),
+        Read(0x1305),
+        Write(0x1305, 0xAA)
+      ]
+    );
+  }
+
+  #[test]
+  fn read_modify_write_writes_back_the_unmodified_value() {
+    // INC $1210,X
+    let mut cpu = cpu(&[0xFE, 0x10, 0x12]);
+    cpu.bus.memory.write(0x1210, 0x05);
+    assert_eq!(
+      step(&mut cpu),
+      [
+        Read(0x0200),
+        Read(0x0201),
+        Read(0x0202),
+        Read(0x1210),
+        Read(0x1210),
+        Write(0x1210, 0x05),
+        Write(0x1210, 0x06)
+      ]
+    );
+  }
+
+  #[test]
+  fn jsr_reads_its_high_byte_after_the_pushes() {
+    // JSR $1234
+    let mut cpu = cpu(&[0x20, 0x34, 0x12]);
+    assert_eq!(
+      step(&mut cpu),
+      [
+        Read(0x0200),
+        Read(0x0201),
+        Read(0x01FD),
+        Write(0x01FD, 0x02),
+        Write(0x01FC, 0x02),
+        Read(0x0202)
+      ]
+    );
+    assert_eq!(cpu.registers.pc.value, 0x1234);
+
+    // Placed in the stack, the low byte of the return address lands on the
+    // high byte of the operand before it is read.
+    let mut cpu = self::cpu(&[]);
+    cpu.load(0x01FC, &[0x20, 0x34, 0x12]);
+    cpu.registers.pc.value = 0x01FC;
+    cpu.registers.sp.value = 0xFF;
+    step(&mut cpu);
+    assert_eq!(cpu.registers.pc.value, 0xFE34);
+  }
+
+  #[test]
+  fn rts_reads_the_pulled_address_before_incrementing_it() {
+    // RTS
+    let mut cpu = cpu(&[0x60]);
+    cpu.registers.sp.value = 0xFB;
+    cpu.bus.memory.write(0x01FC, 0x02);
+    cpu.bus.memory.write(0x01FD, 0x03);
+    assert_eq!(
+      step(&mut cpu),
+      [
+        Read(0x0200),
+        Read(0x0201),
+        Read(0x01FB),
+        Read(0x01FC),
+        Read(0x01FD),
+        Read(0x0302)
+      ]
+    );
+    assert_eq!(cpu.registers.pc.value, 0x0303);
+  }
+
+  #[test]
+  fn pulls_read_the_stack_before_incrementing_it() {
+    // PLA
+    let mut cpu = cpu(&[0x68]);
+    assert_eq!(
+      step(&mut cpu),
+      [Read(0x0200), Read(0x0201), Read(0x01FD), Read(0x01FE)]
+    );
+  }
+
+  #[test]
+  fn taken_branches_read_the_next_opcode_and_the_unfixed_address() {
+    // BNE +$20, from $02F0 to $0312
+    let mut cpu = cpu(&[]);
+    cpu.load(0x02F0, &[0xD0, 0x20]);
+    cpu.registers.pc.value = 0x02F0;
+    assert_eq!(
+      step(&mut cpu),
+      [Read(0x02F0), Read(0x02F1), Read(0x02F2), Read(0x0212)]
+    );
+    assert_eq!(cpu.registers.pc.value, 0x0312);
+  }
+
+  #[test]
+  fn single_cycle_nops_only_read_their_opcode() {
+    // NOP, in the unassigned $x3 column of the 65C02
+    let mut cpu = cpu(&[0x03]);
+    cpu.variant = Variant::Wdc65C02;
+    assert_eq!(step(&mut cpu), [Read(0x0200)]);
+    assert_eq!(cpu.registers.pc.value, 0x0201);
+  }
+
+  #[test]
+  fn interrupts_read_the_displaced_opcode_twice() {
+    // BRK reads its padding byte, an IRQ the opcode it displaces.
+    let mut cpu = cpu(&[0x00]);
+    let status = cpu.registers.flags.to_byte(true);
+    assert_eq!(
+      step(&mut cpu),
+      [
+        Read(0x0200),
+        Read(0x0201),
+        Write(0x01FD, 0x02),
+        Write(0x01FC, 0x02),
+        Write(0x01FB, status),
+        Read(IRQ_VECTOR),
+        Read(IRQ_VECTOR + 1)
+      ]
+    );
+
+    let mut cpu = self::cpu(&[0xEA]);
+    cpu.registers.flags.interrupt_disable = false;
+    let status = cpu.registers.flags.to_byte(false);
+    cpu.irq(true);
+    assert_eq!(
+      step(&mut cpu),
+      [
+        Read(0x0200),
+        Read(0x0200),
+        Write(0x01FD, 0x02),
+        Write(0x01FC, 0x00),
+        Write(0x01FB, status),
+        Read(IRQ_VECTOR),
+        Read(IRQ_VECTOR + 1)
+      ]
+    );
+  }
+}
@@ -0,0 +1,78 @@
+use crate::bus::Bus;
+use crate::instructions::interp::{interp, Variant};
+
+// One disassembled instruction, shown as `C000  A9 05     LDA #$05`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Line {
+  pub address: u16,
+  pub bytes: Vec<u8>,
+  pub text: String,
+}
+
+impl std::fmt::Display for Line {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let bytes: Vec<String> = self
+      .bytes
+      .iter()
+      .map(|byte| format!("{:02X}", byte))
+      .collect();
+    write!(
+      f,
+      "{:04X}  {:<8}  {}",
+      self.address,
+      bytes.join(" "),
+      self.text
+    )
+  }
+}
+
+// Disassemble the instruction at `pc`. Opcodes that do not decode are shown
+// as a single `.byte`, so a listing can carry on past data.
+pub fn disassemble_one<B: Bus>(
+  bus: &B,
+  pc: u16,
+  variant: Variant,
+  undocumented_opcodes: bool,
+) -> Line {
+  let (bytes, text) = match interp(bus, pc, variant, undocumented_opcodes) {
+    Ok(instruction) => {
+      let operand = instruction.operand(pc);
+      let text = if operand.is_empty() {
+        instruction.mnemonic().to_string()
+      } else {
+        format!("{} {}", instruction.mnemonic(), operand)
+      };
+      (instruction.render(), text)
+    }
+    Err(_) => {
+      let byte = bus.peek(pc);
+      (vec![byte], format!(".byte ${:02X}", byte))
+    }
+  };
+
+  Line {
+    address: pc,
+    bytes,
+    text,
+  }
+}
+
+// Disassemble every instruction starting within `start..=end`.
+pub fn disassemble<B: Bus>(
+  bus: &B,
+  start: u16,
+  end: u16,
+  variant: Variant,
+  undocumented_opcodes: bool,
+) -> Vec<Line> {
+  let mut lines = Vec::new();
+  let mut pc = u32::from(start);
+
+  while pc <= u32::from(end) {
+    let line = disassemble_one(bus, pc as u16, variant, undocumented_opcodes);
+    pc += line.bytes.len() as u32;
+    lines.push(line);
+  }
+
+  lines
+}
@@ -1,65 +1,169 @@
 use super::Renderable;
-use crate::memory::Memory;
+use crate::bus::Bus;
 use crate::registers::Registers;
 
-pub(crate) trait AddressMode: Renderable {
+// Operand as written in assembly source.
+pub trait Syntax {
+  fn syntax(&self) -> String;
+}
+
+// Whether an instruction only reads its operand, or also writes it. Indexed
+// modes fix up the address with a dummy read before every write, but only
+// when the index carries into the next page before a read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Access {
+  Read,
+  Write,
+}
+
+pub trait AddressMode: Renderable + Syntax {
   const LENGTH: u16;
-  fn read(&self, memory: &Memory, registers: &Registers) -> i8;
-  fn write(&self, memory: &mut Memory, registers: &mut Registers, value: i8);
+
+  // Effective address, fetching any pointer and making the dummy reads the
+  // hardware makes along the way.
+  fn address<B: Bus>(
+    &self,
+    bus: &mut B,
+    registers: &Registers,
+    access: Access,
+  ) -> u16;
+
+  fn read<B: Bus>(&self, bus: &mut B, registers: &Registers) -> u8 {
+    let addr = self.address(bus, registers, Access::Read);
+    bus.read(addr)
+  }
+  fn write<B: Bus>(&self, bus: &mut B, registers: &mut Registers, value: u8) {
+    let addr = self.address(bus, registers, Access::Write);
+    bus.write(addr, value)
+  }
+
+  // Read-modify-write. The NMOS parts write the unmodified value back while
+  // they work out the result, so that shows up as a dummy write. The 65C02
+  // reads it again instead, which is not modelled.
+  fn modify<B: Bus, F: FnOnce(&mut Registers, u8) -> u8>(
+    &self,
+    bus: &mut B,
+    registers: &mut Registers,
+    f: F,
+  ) -> u8 {
+    let addr = self.address(bus, registers, Access::Write);
+    let value = bus.read(addr);
+    bus.dummy_write(addr, value);
+    let result = f(registers, value);
+    bus.write(addr, result);
+    result
+  }
+
+  // Indexed reads take an extra cycle when they cross a page boundary.
+  fn page_crossed<B: Bus>(&self, _bus: &B, _registers: &Registers) -> bool {
+    false
+  }
+}
+
+fn crosses_page(base: u16, index: u8) -> bool {
+  (base & 0xFF00) != (base.wrapping_add(u16::from(index)) & 0xFF00)
+}
+
+// Add the index, first reading from the address before the carry into the
+// high byte is fixed up when the access needs that cycle.
+fn indexed<B: Bus>(bus: &mut B, base: u16, index: u8, access: Access) -> u16 {
+  let addr = base.wrapping_add(u16::from(index));
+  if access == Access::Write || crosses_page(base, index) {
+    bus.dummy_read((base & 0xFF00) | (addr & 0x00FF));
+  }
+  addr
 }
 
-pub(crate) trait JumpMode {
-  fn dest(&self, memory: &Memory) -> u16;
+pub trait JumpMode: Renderable + Syntax {
+  fn dest<B: Bus>(&self, bus: &mut B, registers: &Registers) -> u16;
 }
 
-pub(crate) struct Accumulator;
+pub struct Accumulator;
 impl AddressMode for Accumulator {
   const LENGTH: u16 = 0;
 
-  fn read(&self, _memory: &Memory, registers: &Registers) -> i8 {
+  fn address<B: Bus>(
+    &self,
+    _bus: &mut B,
+    _registers: &Registers,
+    _access: Access,
+  ) -> u16 {
+    panic!("The accumulator has no address.");
+  }
+  fn read<B: Bus>(&self, _bus: &mut B, registers: &Registers) -> u8 {
     registers.acc.value
   }
-  fn write(&self, _memory: &mut Memory, registers: &mut Registers, value: i8) {
+  fn write<B: Bus>(&self, _bus: &mut B, registers: &mut Registers, value: u8) {
     registers.acc.value = value;
   }
+  fn modify<B: Bus, F: FnOnce(&mut Registers, u8) -> u8>(
+    &self,
+    _bus: &mut B,
+    registers: &mut Registers,
+    f: F,
+  ) -> u8 {
+    let value = registers.acc.value;
+    registers.acc.value = f(registers, value);
+    registers.acc.value
+  }
 }
 impl Renderable for Accumulator {
   fn render(&self) -> Vec<u8> {
     vec![]
   }
 }
+impl Syntax for Accumulator {
+  fn syntax(&self) -> String {
+    "A".to_string()
+  }
+}
 
-pub(crate) struct Immediate(pub i8);
+pub struct Immediate(pub u8);
 impl AddressMode for Immediate {
   const LENGTH: u16 = 1;
 
-  fn read(&self, _memory: &Memory, _registers: &Registers) -> i8 {
+  fn address<B: Bus>(
+    &self,
+    _bus: &mut B,
+    _registers: &Registers,
+    _access: Access,
+  ) -> u16 {
+    panic!("Immediate values have no address.");
+  }
+  fn read<B: Bus>(&self, _bus: &mut B, _registers: &Registers) -> u8 {
     self.0
   }
-  fn write(
+  fn write<B: Bus>(
     &self,
-    _memory: &mut Memory,
+    _bus: &mut B,
     _registers: &mut Registers,
-    _value: i8,
+    _value: u8,
   ) {
     panic!("Cannot write to immediate value.");
   }
 }
 impl Renderable for Immediate {
   fn render(&self) -> Vec<u8> {
-    vec![self.0 as u8]
+    vec![self.0]
+  }
+}
+impl Syntax for Immediate {
+  fn syntax(&self) -> String {
+    format!("#${:02X}", self.0)
   }
 }
 
-pub(crate) struct ZeroPage(pub u8);
+pub struct ZeroPage(pub u8);
 impl AddressMode for ZeroPage {
   const LENGTH: u16 = 1;
 
-  fn read(&self, memory: &Memory, _registers: &Registers) -> i8 {
-    memory.zero_page(self.0) as i8
-  }
-  fn write(&self, memory: &mut Memory, _registers: &mut Registers, value: i8) {
-    memory.zero_page_write(self.0, value as u8)
+  fn address<B: Bus>(
+    &self,
+    _bus: &mut B,
+    _registers: &Registers,
+    _access: Access,
+  ) -> u16 {
+    u16::from(self.0)
   }
 }
 impl Renderable for ZeroPage {
@@ -67,16 +171,24 @@ impl Renderable for ZeroPage {
     vec![self.0]
   }
 }
+impl Syntax for ZeroPage {
+  fn syntax(&self) -> String {
+    format!("${:02X}", self.0)
+  }
+}
 
-pub(crate) struct ZeroPageX(pub u8);
+pub struct ZeroPageX(pub u8);
 impl AddressMode for ZeroPageX {
   const LENGTH: u16 = 1;
 
-  fn read(&self, memory: &Memory, registers: &Registers) -> i8 {
-    memory.zero_page_register(self.0, &registers.x) as i8
-  }
-  fn write(&self, memory: &mut Memory, registers: &mut Registers, value: i8) {
-    memory.zero_page_register_write(self.0, &registers.x, value as u8)
+  fn address<B: Bus>(
+    &self,
+    bus: &mut B,
+    registers: &Registers,
+    _access: Access,
+  ) -> u16 {
+    bus.dummy_read(u16::from(self.0));
+    u16::from(self.0.wrapping_add(registers.x.value))
   }
 }
 impl Renderable for ZeroPageX {
@@ -84,16 +196,24 @@ impl Renderable for ZeroPageX {
     vec![self.0]
   }
 }
+impl Syntax for ZeroPageX {
+  fn syntax(&self) -> String {
+    format!("${:02X},X", self.0)
+  }
+}
 
-pub(crate) struct ZeroPageY(pub u8);
+pub struct ZeroPageY(pub u8);
 impl AddressMode for ZeroPageY {
   const LENGTH: u16 = 1;
 
-  fn read(&self, memory: &Memory, registers: &Registers) -> i8 {
-    memory.zero_page_register(self.0, &registers.y) as i8
-  }
-  fn write(&self, memory: &mut Memory, registers: &mut Registers, value: i8) {
-    memory.zero_page_register_write(self.0, &registers.y, value as u8)
+  fn address<B: Bus>(
+    &self,
+    bus: &mut B,
+    registers: &Registers,
+    _access: Access,
+  ) -> u16 {
+    bus.dummy_read(u16::from(self.0));
+    u16::from(self.0.wrapping_add(registers.y.value))
   }
 }
 impl Renderable for ZeroPageY {
@@ -101,16 +221,23 @@ impl Renderable for ZeroPageY {
     vec![self.0]
   }
 }
+impl Syntax for ZeroPageY {
+  fn syntax(&self) -> String {
+    format!("${:02X},Y", self.0)
+  }
+}
 
-pub(crate) struct Absolute(pub u16);
+pub struct Absolute(pub u16);
 impl AddressMode for Absolute {
   const LENGTH: u16 = 2;
 
-  fn read(&self, memory: &Memory, _registers: &Registers) -> i8 {
-    memory.absolute(self.0) as i8
-  }
-  fn write(&self, memory: &mut Memory, _registers: &mut Registers, value: i8) {
-    memory.absolute_write(self.0, value as u8)
+  fn address<B: Bus>(
+    &self,
+    _bus: &mut B,
+    _registers: &Registers,
+    _access: Access,
+  ) -> u16 {
+    self.0
   }
 }
 impl Renderable for Absolute {
@@ -118,21 +245,31 @@ impl Renderable for Absolute {
     self.0.to_le_bytes().to_vec()
   }
 }
+impl Syntax for Absolute {
+  fn syntax(&self) -> String {
+    format!("${:04X}", self.0)
+  }
+}
 impl JumpMode for Absolute {
-  fn dest(&self, _memory: &Memory) -> u16 {
+  fn dest<B: Bus>(&self, _bus: &mut B, _registers: &Registers) -> u16 {
     self.0
   }
 }
 
-pub(crate) struct AbsoluteX(pub u16);
+pub struct AbsoluteX(pub u16);
 impl AddressMode for AbsoluteX {
   const LENGTH: u16 = 2;
 
-  fn read(&self, memory: &Memory, registers: &Registers) -> i8 {
-    memory.absolute_register(self.0, &registers.x) as i8
+  fn address<B: Bus>(
+    &self,
+    bus: &mut B,
+    registers: &Registers,
+    access: Access,
+  ) -> u16 {
+    indexed(bus, self.0, registers.x.value, access)
   }
-  fn write(&self, memory: &mut Memory, registers: &mut Registers, value: i8) {
-    memory.absolute_register_write(self.0, &registers.x, value as u8)
+  fn page_crossed<B: Bus>(&self, _bus: &B, registers: &Registers) -> bool {
+    crosses_page(self.0, registers.x.value)
   }
 }
 impl Renderable for AbsoluteX {
@@ -140,16 +277,26 @@ impl Renderable for AbsoluteX {
     self.0.to_le_bytes().to_vec()
   }
 }
+impl Syntax for AbsoluteX {
+  fn syntax(&self) -> String {
+    format!("${:04X},X", self.0)
+  }
+}
 
-pub(crate) struct AbsoluteY(pub u16);
+pub struct AbsoluteY(pub u16);
 impl AddressMode for AbsoluteY {
   const LENGTH: u16 = 2;
 
-  fn read(&self, memory: &Memory, registers: &Registers) -> i8 {
-    memory.absolute_register(self.0, &registers.y) as i8
+  fn address<B: Bus>(
+    &self,
+    bus: &mut B,
+    registers: &Registers,
+    access: Access,
+  ) -> u16 {
+    indexed(bus, self.0, registers.y.value, access)
   }
-  fn write(&self, memory: &mut Memory, registers: &mut Registers, value: i8) {
-    memory.absolute_register_write(self.0, &registers.y, value as u8)
+  fn page_crossed<B: Bus>(&self, _bus: &B, registers: &Registers) -> bool {
+    crosses_page(self.0, registers.y.value)
   }
 }
 impl Renderable for AbsoluteY {
@@ -157,11 +304,16 @@ impl Renderable for AbsoluteY {
     self.0.to_le_bytes().to_vec()
   }
 }
+impl Syntax for AbsoluteY {
+  fn syntax(&self) -> String {
+    format!("${:04X},Y", self.0)
+  }
+}
 
-pub(crate) struct Indirect(pub u16);
+pub struct Indirect(pub u16);
 impl JumpMode for Indirect {
-  fn dest(&self, memory: &Memory) -> u16 {
-    memory.indirect(self.0)
+  fn dest<B: Bus>(&self, bus: &mut B, _registers: &Registers) -> u16 {
+    bus.indirect(self.0)
   }
 }
 impl Renderable for Indirect {
@@ -169,16 +321,60 @@ impl Renderable for Indirect {
     self.0.to_le_bytes().to_vec()
   }
 }
+impl Syntax for Indirect {
+  fn syntax(&self) -> String {
+    format!("(${:04X})", self.0)
+  }
+}
+
+// 65C02 indirect jump, which carries into the next page for a pointer at $xxFF.
+pub struct IndirectFixed(pub u16);
+impl JumpMode for IndirectFixed {
+  fn dest<B: Bus>(&self, bus: &mut B, _registers: &Registers) -> u16 {
+    bus.absolute_pointer(self.0)
+  }
+}
+impl Renderable for IndirectFixed {
+  fn render(&self) -> Vec<u8> {
+    self.0.to_le_bytes().to_vec()
+  }
+}
+impl Syntax for IndirectFixed {
+  fn syntax(&self) -> String {
+    format!("(${:04X})", self.0)
+  }
+}
+
+// 65C02 jump through a table, indexed by X.
+pub struct AbsoluteIndexedIndirect(pub u16);
+impl JumpMode for AbsoluteIndexedIndirect {
+  fn dest<B: Bus>(&self, bus: &mut B, registers: &Registers) -> u16 {
+    bus.absolute_pointer(self.0.wrapping_add(u16::from(registers.x.value)))
+  }
+}
+impl Renderable for AbsoluteIndexedIndirect {
+  fn render(&self) -> Vec<u8> {
+    self.0.to_le_bytes().to_vec()
+  }
+}
+impl Syntax for AbsoluteIndexedIndirect {
+  fn syntax(&self) -> String {
+    format!("(${:04X},X)", self.0)
+  }
+}
 
-pub(crate) struct IndexedIndirect(pub u8);
+pub struct IndexedIndirect(pub u8);
 impl AddressMode for IndexedIndirect {
   const LENGTH: u16 = 1;
 
-  fn read(&self, memory: &Memory, registers: &Registers) -> i8 {
-    memory.indexed_indirect(self.0, &registers.x) as i8
-  }
-  fn write(&self, memory: &mut Memory, registers: &mut Registers, value: i8) {
-    memory.indexed_indirect_write(self.0, &registers.x, value as u8)
+  fn address<B: Bus>(
+    &self,
+    bus: &mut B,
+    registers: &Registers,
+    _access: Access,
+  ) -> u16 {
+    bus.dummy_read(u16::from(self.0));
+    bus.zero_page_pointer(self.0.wrapping_add(registers.x.value))
   }
 }
 impl Renderable for IndexedIndirect {
@@ -186,16 +382,31 @@ impl Renderable for IndexedIndirect {
     vec![self.0]
   }
 }
+impl Syntax for IndexedIndirect {
+  fn syntax(&self) -> String {
+    format!("(${:02X},X)", self.0)
+  }
+}
 
-pub(crate) struct IndirectIndexed(pub u8);
+pub struct IndirectIndexed(pub u8);
 impl AddressMode for IndirectIndexed {
   const LENGTH: u16 = 1;
 
-  fn read(&self, memory: &Memory, registers: &Registers) -> i8 {
-    memory.indirect_indexed(self.0, &registers.y) as i8
-  }
-  fn write(&self, memory: &mut Memory, registers: &mut Registers, value: i8) {
-    memory.indirect_indexed_write(self.0, &registers.y, value as u8)
+  fn address<B: Bus>(
+    &self,
+    bus: &mut B,
+    registers: &Registers,
+    access: Access,
+  ) -> u16 {
+    let base = bus.zero_page_pointer(self.0);
+    indexed(bus, base, registers.y.value, access)
+  }
+  fn page_crossed<B: Bus>(&self, bus: &B, registers: &Registers) -> bool {
+    let base = u16::from_le_bytes([
+      bus.peek(u16::from(self.0)),
+      bus.peek(u16::from(self.0.wrapping_add(1))),
+    ]);
+    crosses_page(base, registers.y.value)
   }
 }
 impl Renderable for IndirectIndexed {
@@ -203,3 +414,33 @@ impl Renderable for IndirectIndexed {
     vec![self.0]
   }
 }
+impl Syntax for IndirectIndexed {
+  fn syntax(&self) -> String {
+    format!("(${:02X}),Y", self.0)
+  }
+}
+
+// 65C02 (zp) mode, an unindexed pointer in the zero page.
+pub struct ZeroPageIndirect(pub u8);
+impl AddressMode for ZeroPageIndirect {
+  const LENGTH: u16 = 1;
+
+  fn address<B: Bus>(
+    &self,
+    bus: &mut B,
+    _registers: &Registers,
+    _access: Access,
+  ) -> u16 {
+    bus.zero_page_pointer(self.0)
+  }
+}
+impl Renderable for ZeroPageIndirect {
+  fn render(&self) -> Vec<u8> {
+    vec![self.0]
+  }
+}
+impl Syntax for ZeroPageIndirect {
+  fn syntax(&self) -> String {
+    format!("(${:02X})", self.0)
+  }
+}
@@ -0,0 +1,834 @@
+use super::addressing::*;
+use super::interp::DecodedInstruction;
+use super::undocumented::{Alias, IGN};
+use super::*;
+
+// WDC 65C02 additions to the NMOS instruction set.
+
+// Existing instructions gain the (zp) addressing mode.
+impl ADCAddressMode for ZeroPageIndirect {}
+impl ANDAddressMode for ZeroPageIndirect {}
+impl CMPAddressMode for ZeroPageIndirect {}
+impl EORAddressMode for ZeroPageIndirect {}
+impl LDAAddressMode for ZeroPageIndirect {}
+impl ORAAddressMode for ZeroPageIndirect {}
+impl SBCAddressMode for ZeroPageIndirect {}
+impl STAAddressMode for ZeroPageIndirect {}
+
+impl Renderable for ORA<ZeroPageIndirect> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x12], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for ORA<ZeroPageIndirect> {
+  const CYCLES: u8 = 5;
+}
+
+impl Renderable for AND<ZeroPageIndirect> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x32], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for AND<ZeroPageIndirect> {
+  const CYCLES: u8 = 5;
+}
+
+impl Renderable for EOR<ZeroPageIndirect> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x52], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for EOR<ZeroPageIndirect> {
+  const CYCLES: u8 = 5;
+}
+
+impl Renderable for ADC<ZeroPageIndirect> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x72], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for ADC<ZeroPageIndirect> {
+  const CYCLES: u8 = 5;
+}
+
+impl Renderable for STA<ZeroPageIndirect> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x92], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for STA<ZeroPageIndirect> {
+  const CYCLES: u8 = 5;
+}
+
+impl Renderable for LDA<ZeroPageIndirect> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xB2], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for LDA<ZeroPageIndirect> {
+  const CYCLES: u8 = 5;
+}
+
+impl Renderable for CMP<ZeroPageIndirect> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xD2], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for CMP<ZeroPageIndirect> {
+  const CYCLES: u8 = 5;
+}
+
+impl Renderable for SBC<ZeroPageIndirect> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xF2], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for SBC<ZeroPageIndirect> {
+  const CYCLES: u8 = 5;
+}
+
+// INC A and DEC A.
+impl INCAddressMode for Accumulator {}
+impl DECAddressMode for Accumulator {}
+
+impl Renderable for INC<Accumulator> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x1A], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for INC<Accumulator> {
+  const CYCLES: u8 = 2;
+}
+
+impl Renderable for DEC<Accumulator> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x3A], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for DEC<Accumulator> {
+  const CYCLES: u8 = 2;
+}
+
+impl BITAddressMode for Immediate {
+  const COPIES_BITS: bool = false;
+}
+
+impl Renderable for BIT<Immediate> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x89], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for BIT<Immediate> {
+  const CYCLES: u8 = 2;
+}
+
+impl BITAddressMode for ZeroPageX {}
+impl BITAddressMode for AbsoluteX {}
+
+impl Renderable for BIT<ZeroPageX> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x34], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for BIT<ZeroPageX> {
+  const CYCLES: u8 = 4;
+}
+
+impl Renderable for BIT<AbsoluteX> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x3C], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for BIT<AbsoluteX> {
+  const CYCLES: u8 = 4;
+}
+
+impl JMPAddressMode for IndirectFixed {}
+impl JMPAddressMode for AbsoluteIndexedIndirect {}
+
+impl Renderable for JMP<IndirectFixed> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x6C], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for JMP<IndirectFixed> {
+  const CYCLES: u8 = 6;
+}
+
+impl Renderable for JMP<AbsoluteIndexedIndirect> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x7C], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for JMP<AbsoluteIndexedIndirect> {
+  const CYCLES: u8 = 6;
+}
+
+pub struct BRA(pub i8);
+impl<B: Bus> Instruction<B> for BRA {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.pc.value = registers.pc.value.wrapping_add(2);
+    branch(bus, registers, self.0)
+  }
+}
+
+impl Mnemonic for BRA {
+  fn mnemonic(&self) -> &'static str {
+    "BRA"
+  }
+  fn operand(&self, pc: u16) -> String {
+    format!("${:04X}", pc.wrapping_add(2).wrapping_add(self.0 as u16))
+  }
+}
+
+impl Renderable for BRA {
+  fn render(&self) -> Vec<u8> {
+    vec![0x80, self.0 as u8]
+  }
+}
+
+pub struct PHX;
+impl<B: Bus> Instruction<B> for PHX {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let value = registers.x.value;
+    push(bus, registers, value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    3
+  }
+}
+
+impl Mnemonic for PHX {
+  fn mnemonic(&self) -> &'static str {
+    "PHX"
+  }
+}
+
+impl Renderable for PHX {
+  fn render(&self) -> Vec<u8> {
+    vec![0xDA]
+  }
+}
+
+pub struct PLX;
+impl<B: Bus> Instruction<B> for PLX {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    stack_dummy_read(bus, registers);
+    let value = pull(bus, registers);
+    registers.x.value = value;
+    registers.flags.update_zero_negative(value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    4
+  }
+}
+
+impl Mnemonic for PLX {
+  fn mnemonic(&self) -> &'static str {
+    "PLX"
+  }
+}
+
+impl Renderable for PLX {
+  fn render(&self) -> Vec<u8> {
+    vec![0xFA]
+  }
+}
+
+pub struct PHY;
+impl<B: Bus> Instruction<B> for PHY {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let value = registers.y.value;
+    push(bus, registers, value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    3
+  }
+}
+
+impl Mnemonic for PHY {
+  fn mnemonic(&self) -> &'static str {
+    "PHY"
+  }
+}
+
+impl Renderable for PHY {
+  fn render(&self) -> Vec<u8> {
+    vec![0x5A]
+  }
+}
+
+pub struct PLY;
+impl<B: Bus> Instruction<B> for PLY {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    stack_dummy_read(bus, registers);
+    let value = pull(bus, registers);
+    registers.y.value = value;
+    registers.flags.update_zero_negative(value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    4
+  }
+}
+
+impl Mnemonic for PLY {
+  fn mnemonic(&self) -> &'static str {
+    "PLY"
+  }
+}
+
+impl Renderable for PLY {
+  fn render(&self) -> Vec<u8> {
+    vec![0x7A]
+  }
+}
+
+// Store zero.
+pub trait STZAddressMode: AddressMode {}
+impl STZAddressMode for ZeroPage {}
+impl STZAddressMode for ZeroPageX {}
+impl STZAddressMode for Absolute {}
+impl STZAddressMode for AbsoluteX {}
+
+pub struct STZ<T: STZAddressMode>(pub T);
+impl<B: Bus, T: STZAddressMode> Instruction<B> for STZ<T>
+where
+  STZ<T>: Renderable + Cycles,
+{
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    self.0.write(bus, registers, 0);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
+
+impl<T: STZAddressMode> Mnemonic for STZ<T> {
+  fn mnemonic(&self) -> &'static str {
+    "STZ"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
+  }
+}
+
+impl Renderable for STZ<ZeroPage> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x64], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for STZ<ZeroPage> {
+  const CYCLES: u8 = 3;
+}
+
+impl Renderable for STZ<ZeroPageX> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x74], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for STZ<ZeroPageX> {
+  const CYCLES: u8 = 4;
+}
+
+impl Renderable for STZ<Absolute> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x9C], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for STZ<Absolute> {
+  const CYCLES: u8 = 4;
+}
+
+impl Renderable for STZ<AbsoluteX> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x9E], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for STZ<AbsoluteX> {
+  const CYCLES: u8 = 5;
+}
+
+// Test and reset bits: Z from A AND M, then clear the bits of A in M.
+pub trait TRBAddressMode: AddressMode {}
+impl TRBAddressMode for ZeroPage {}
+impl TRBAddressMode for Absolute {}
+
+pub struct TRB<T: TRBAddressMode>(pub T);
+impl<B: Bus, T: TRBAddressMode> Instruction<B> for TRB<T>
+where
+  TRB<T>: Renderable + Cycles,
+{
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    self.0.modify(bus, registers, |registers, value| {
+      registers.flags.zero = (value & registers.acc.value) == 0;
+      value & !registers.acc.value
+    });
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
+
+impl<T: TRBAddressMode> Mnemonic for TRB<T> {
+  fn mnemonic(&self) -> &'static str {
+    "TRB"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
+  }
+}
+
+impl Renderable for TRB<ZeroPage> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x14], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for TRB<ZeroPage> {
+  const CYCLES: u8 = 5;
+}
+
+impl Renderable for TRB<Absolute> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x1C], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for TRB<Absolute> {
+  const CYCLES: u8 = 6;
+}
+
+// Test and set bits: Z from A AND M, then set the bits of A in M.
+pub trait TSBAddressMode: AddressMode {}
+impl TSBAddressMode for ZeroPage {}
+impl TSBAddressMode for Absolute {}
+
+pub struct TSB<T: TSBAddressMode>(pub T);
+impl<B: Bus, T: TSBAddressMode> Instruction<B> for TSB<T>
+where
+  TSB<T>: Renderable + Cycles,
+{
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    self.0.modify(bus, registers, |registers, value| {
+      registers.flags.zero = (value & registers.acc.value) == 0;
+      value | registers.acc.value
+    });
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
+
+impl<T: TSBAddressMode> Mnemonic for TSB<T> {
+  fn mnemonic(&self) -> &'static str {
+    "TSB"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
+  }
+}
+
+impl Renderable for TSB<ZeroPage> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x04], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for TSB<ZeroPage> {
+  const CYCLES: u8 = 5;
+}
+
+impl Renderable for TSB<Absolute> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x0C], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for TSB<Absolute> {
+  const CYCLES: u8 = 6;
+}
+
+// In decimal mode the 65C02 takes an extra cycle over ADC and SBC to set N
+// and Z from the BCD result, where the NMOS parts leave them invalid. The
+// result itself only differs for digits that are not valid BCD.
+pub struct Decimal<I>(pub I);
+impl<B: Bus, I: Instruction<B>> Instruction<B> for Decimal<I> {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let cycles = self.0.evaluate(bus, registers);
+    if !registers.flags.decimal_mode {
+      return cycles;
+    }
+
+    registers.flags.update_zero_negative(registers.acc.value);
+    cycles + 1
+  }
+}
+
+impl<I: Mnemonic> Mnemonic for Decimal<I> {
+  fn mnemonic(&self) -> &'static str {
+    self.0.mnemonic()
+  }
+  fn operand(&self, pc: u16) -> String {
+    self.0.operand(pc)
+  }
+}
+
+impl<I: Renderable> Renderable for Decimal<I> {
+  fn render(&self) -> Vec<u8> {
+    self.0.render()
+  }
+}
+
+// The 65C02 leaves decimal mode whenever it takes an interrupt, BRK
+// included, after the status has been pushed.
+pub struct ClearDecimal<I>(pub I);
+impl<B: Bus, I: Instruction<B>> Instruction<B> for ClearDecimal<I> {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let cycles = self.0.evaluate(bus, registers);
+    registers.flags.decimal_mode = false;
+
+    cycles
+  }
+}
+
+impl<I: Mnemonic> Mnemonic for ClearDecimal<I> {
+  fn mnemonic(&self) -> &'static str {
+    self.0.mnemonic()
+  }
+  fn operand(&self, pc: u16) -> String {
+    self.0.operand(pc)
+  }
+}
+
+impl<I: Renderable> Renderable for ClearDecimal<I> {
+  fn render(&self) -> Vec<u8> {
+    self.0.render()
+  }
+}
+
+// Unassigned opcodes in the $x3 and $xB columns, which the 65C02 runs as one
+// byte NOPs taking a single cycle.
+pub struct NOP1(pub u8);
+
+// Whether the opcode decodes to a `NOP1`, which unlike every other
+// instruction does not read the byte after its opcode.
+pub fn single_cycle_nop(opcode: u8) -> bool {
+  let column = opcode & 0x0F;
+  (column == 0x03 || column == 0x0B)
+    && opcode != WAI_OPCODE
+    && opcode != STP_OPCODE
+}
+
+impl<B: Bus> Instruction<B> for NOP1 {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    1
+  }
+}
+
+impl Mnemonic for NOP1 {
+  fn mnemonic(&self) -> &'static str {
+    "NOP"
+  }
+}
+
+impl Renderable for NOP1 {
+  fn render(&self) -> Vec<u8> {
+    vec![self.0]
+  }
+}
+
+// Unassigned $5C, a three byte NOP that takes eight cycles.
+pub struct NOP8(pub u16);
+impl<B: Bus> Instruction<B> for NOP8 {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.pc.value = registers.pc.value.wrapping_add(3);
+
+    8
+  }
+}
+
+impl Mnemonic for NOP8 {
+  fn mnemonic(&self) -> &'static str {
+    "NOP"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    format!("${:04X}", self.0)
+  }
+}
+
+impl Renderable for NOP8 {
+  fn render(&self) -> Vec<u8> {
+    [&[0x5C], &self.0.to_le_bytes()[..]].concat()
+  }
+}
+
+// WAI and STP only advance past themselves here. The CPU notices them by
+// opcode and stops the clock until an interrupt or a reset.
+pub const WAI_OPCODE: u8 = 0xCB;
+pub const STP_OPCODE: u8 = 0xDB;
+
+pub struct WAI;
+impl<B: Bus> Instruction<B> for WAI {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    3
+  }
+}
+
+impl Mnemonic for WAI {
+  fn mnemonic(&self) -> &'static str {
+    "WAI"
+  }
+}
+
+impl Renderable for WAI {
+  fn render(&self) -> Vec<u8> {
+    vec![WAI_OPCODE]
+  }
+}
+
+pub struct STP;
+impl<B: Bus> Instruction<B> for STP {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    3
+  }
+}
+
+impl Mnemonic for STP {
+  fn mnemonic(&self) -> &'static str {
+    "STP"
+  }
+}
+
+impl Renderable for STP {
+  fn render(&self) -> Vec<u8> {
+    vec![STP_OPCODE]
+  }
+}
+
+// Decode the opcodes the 65C02 adds or changes, or None to fall back to the
+// NMOS decoder.
+pub(crate) fn interp<B: Bus>(
+  bus: &B,
+  pc: u16,
+) -> Option<DecodedInstruction<B>> {
+  let op = bus.peek(pc);
+  let byte = || bus.peek(pc.wrapping_add(1));
+  let word = || {
+    u16::from_le_bytes([
+      bus.peek(pc.wrapping_add(1)),
+      bus.peek(pc.wrapping_add(2)),
+    ])
+  };
+
+  let instruction: DecodedInstruction<B> = match op {
+    0x12 => Box::new(ORA(ZeroPageIndirect(byte()))),
+    0x32 => Box::new(AND(ZeroPageIndirect(byte()))),
+    0x52 => Box::new(EOR(ZeroPageIndirect(byte()))),
+    0x72 => Box::new(Decimal(ADC(ZeroPageIndirect(byte())))),
+    0x92 => Box::new(STA(ZeroPageIndirect(byte()))),
+    0xB2 => Box::new(LDA(ZeroPageIndirect(byte()))),
+    0xD2 => Box::new(CMP(ZeroPageIndirect(byte()))),
+    0xF2 => Box::new(Decimal(SBC(ZeroPageIndirect(byte())))),
+
+    0x1A => Box::new(INC(Accumulator)),
+    0x3A => Box::new(DEC(Accumulator)),
+
+    0x89 => Box::new(BIT(Immediate(byte()))),
+    0x34 => Box::new(BIT(ZeroPageX(byte()))),
+    0x3C => Box::new(BIT(AbsoluteX(word()))),
+
+    0x00 => Box::new(ClearDecimal(BRK)),
+
+    0x6C => Box::new(JMP(IndirectFixed(word()))),
+    0x7C => Box::new(JMP(AbsoluteIndexedIndirect(word()))),
+
+    0x80 => Box::new(BRA(byte() as i8)),
+
+    0xDA => Box::new(PHX),
+    0xFA => Box::new(PLX),
+    0x5A => Box::new(PHY),
+    0x7A => Box::new(PLY),
+
+    0x64 => Box::new(STZ(ZeroPage(byte()))),
+    0x74 => Box::new(STZ(ZeroPageX(byte()))),
+    0x9C => Box::new(STZ(Absolute(word()))),
+    0x9E => Box::new(STZ(AbsoluteX(word()))),
+
+    0x14 => Box::new(TRB(ZeroPage(byte()))),
+    0x1C => Box::new(TRB(Absolute(word()))),
+    0x04 => Box::new(TSB(ZeroPage(byte()))),
+    0x0C => Box::new(TSB(Absolute(word()))),
+
+    WAI_OPCODE => Box::new(WAI),
+    STP_OPCODE => Box::new(STP),
+
+    0x02 | 0x22 | 0x42 | 0x62 | 0x82 | 0xC2 | 0xE2 => {
+      Box::new(Alias(op, IGN(Immediate(byte()))))
+    }
+    0x44 => Box::new(Alias(op, IGN(ZeroPage(byte())))),
+    0x54 | 0xD4 | 0xF4 => Box::new(Alias(op, IGN(ZeroPageX(byte())))),
+    0xDC | 0xFC => Box::new(Alias(op, IGN(Absolute(word())))),
+    0x5C => Box::new(NOP8(word())),
+    op if single_cycle_nop(op) => Box::new(NOP1(op)),
+    _ => return None,
+  };
+
+  Some(instruction)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::super::interp::{interp, Variant};
+  use super::*;
+  use crate::cpu::Cpu;
+
+  const ORIGIN: u16 = 0x0200;
+
+  fn cpu(variant: Variant, program: &[u8]) -> Cpu {
+    let mut cpu = Cpu::new();
+    cpu.variant = variant;
+    cpu.load(ORIGIN, program);
+    cpu.registers.pc.value = ORIGIN;
+    cpu
+  }
+
+  // Cycles taken by the next instruction.
+  fn step(cpu: &mut Cpu) -> u64 {
+    let before = cpu.cycles;
+    cpu.step().unwrap();
+    cpu.cycles - before
+  }
+
+  #[test]
+  fn bit_indexes_by_x() {
+    // LDX #$01, BIT $0F,X, BIT $12FF,X
+    let mut cpu = cpu(
+      Variant::Wdc65C02,
+      &[0xA2, 0x01, 0x34, 0x0F, 0x3C, 0xFF, 0x12],
+    );
+    cpu.bus.write(0x0010, 0xC0);
+    cpu.bus.write(0x1300, 0x40);
+    step(&mut cpu);
+
+    assert_eq!(step(&mut cpu), 4);
+    assert!(cpu.registers.flags.negative && cpu.registers.flags.overflow);
+    // Crossing into the next page costs a cycle.
+    assert_eq!(step(&mut cpu), 5);
+    assert!(!cpu.registers.flags.negative && cpu.registers.flags.overflow);
+    assert_eq!(cpu.registers.pc.value, ORIGIN + 7);
+  }
+
+  #[test]
+  fn decimal_arithmetic_sets_n_and_z_from_the_result() {
+    // SED, LDA #$99, ADC #$01, SBC #$00
+    let program = [0xF8, 0xA9, 0x99, 0x69, 0x01, 0xE9, 0x00];
+    let mut cpu = cpu(Variant::Wdc65C02, &program);
+    step(&mut cpu);
+    step(&mut cpu);
+
+    assert_eq!(step(&mut cpu), 3);
+    assert_eq!(cpu.registers.acc.value, 0x00);
+    assert!(cpu.registers.flags.zero && !cpu.registers.flags.negative);
+    assert!(cpu.registers.flags.carry);
+
+    assert_eq!(step(&mut cpu), 3);
+    assert_eq!(cpu.registers.acc.value, 0x00);
+    assert!(cpu.registers.flags.zero);
+
+    // The NMOS part takes Z from the binary sum instead.
+    let mut nmos = self::cpu(Variant::Nmos6502, &program);
+    step(&mut nmos);
+    step(&mut nmos);
+    assert_eq!(step(&mut nmos), 2);
+    assert!(!nmos.registers.flags.zero && nmos.registers.flags.negative);
+  }
+
+  #[test]
+  fn interrupts_clear_decimal_mode() {
+    // SED, BRK
+    let mut cpu = cpu(Variant::Wdc65C02, &[0xF8, 0x00]);
+    step(&mut cpu);
+    step(&mut cpu);
+    assert!(!cpu.registers.flags.decimal_mode);
+    // D is only cleared after the status is pushed.
+    assert_ne!(cpu.bus.read(0x01FD) & 0x08, 0);
+
+    let mut cpu = self::cpu(Variant::Wdc65C02, &[0xF8, 0x58]);
+    step(&mut cpu);
+    step(&mut cpu);
+    cpu.irq(true);
+    step(&mut cpu);
+    assert!(!cpu.registers.flags.decimal_mode);
+
+    let mut nmos = self::cpu(Variant::Nmos6502, &[0xF8, 0x00]);
+    step(&mut nmos);
+    step(&mut nmos);
+    assert!(nmos.registers.flags.decimal_mode);
+  }
+
+  #[test]
+  fn unassigned_opcodes_are_nops() {
+    // Opcode, length and cycles.
+    let nops = [
+      (0x02, 2, 2),
+      (0x44, 2, 3),
+      (0x54, 2, 4),
+      (0xDC, 3, 4),
+      (0x5C, 3, 8),
+      (0x03, 1, 1),
+      (0xFB, 1, 1),
+    ];
+    for &(opcode, length, cycles) in &nops {
+      let mut cpu = cpu(Variant::Wdc65C02, &[opcode, 0x12, 0x34]);
+      assert_eq!(step(&mut cpu), cycles, "${:02X}", opcode);
+      assert_eq!(cpu.registers.pc.value, ORIGIN + length, "${:02X}", opcode);
+    }
+  }
+
+  #[test]
+  fn only_bit_instructions_are_illegal() {
+    let mut memory = Memory::new();
+    let illegal: Vec<u8> = (0..=0xFF)
+      .filter(|&opcode| {
+        memory.write(ORIGIN, opcode);
+        interp(&memory, ORIGIN, Variant::Wdc65C02, false).is_err()
+      })
+      .collect();
+
+    let bit_instructions: Vec<u8> =
+      (0..=0xFF).filter(|opcode| opcode & 0x07 == 0x07).collect();
+    assert_eq!(illegal, bit_instructions);
+  }
+}
@@ -1,136 +1,140 @@
 use super::addressing::*;
 use super::*;
-use crate::memory::Memory;
-
-pub(crate) fn interp(memory: &Memory, pc: u16) -> Box<dyn Instruction> {
-  let op = memory.absolute(pc);
-
-  match op {
-    0x69 => Box::new(ADC(Immediate(memory.absolute(pc + 1) as i8))),
-    0x65 => Box::new(ADC(ZeroPage(memory.absolute(pc + 1)))),
-    0x75 => Box::new(ADC(ZeroPageX(memory.absolute(pc + 1)))),
-    0x6D => Box::new(ADC(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0x7D => Box::new(ADC(AbsoluteX(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0x79 => Box::new(ADC(AbsoluteY(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0x61 => Box::new(ADC(IndexedIndirect(memory.absolute(pc + 1)))),
-    0x71 => Box::new(ADC(IndirectIndexed(memory.absolute(pc + 1)))),
-
-    0x29 => Box::new(AND(Immediate(memory.absolute(pc + 1) as i8))),
-    0x25 => Box::new(AND(ZeroPage(memory.absolute(pc + 1)))),
-    0x35 => Box::new(AND(ZeroPageX(memory.absolute(pc + 1)))),
-    0x2D => Box::new(AND(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0x3D => Box::new(AND(AbsoluteX(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0x39 => Box::new(AND(AbsoluteY(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0x21 => Box::new(AND(IndexedIndirect(memory.absolute(pc + 1)))),
-    0x31 => Box::new(AND(IndirectIndexed(memory.absolute(pc + 1)))),
+use crate::bus::Bus;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Variant {
+  #[default]
+  Nmos6502,
+  // NES CPU, without decimal mode
+  Ricoh2A03,
+  // CMOS part with extra instructions, valid N and Z in decimal mode, D
+  // cleared on interrupts and the JMP ($xxFF) bug fixed. Unassigned opcodes
+  // are NOPs, but the RMB, SMB, BBR and BBS bit instructions in the $x7 and
+  // $xF columns are not implemented and decode as illegal.
+  Wdc65C02,
+}
+
+pub(super) fn arithmetic<B: Bus, I: Instruction<B> + 'static>(
+  instruction: I,
+  variant: Variant,
+) -> Box<dyn Instruction<B>> {
+  match variant {
+    Variant::Nmos6502 => Box::new(instruction),
+    Variant::Ricoh2A03 => Box::new(Binary(instruction)),
+    Variant::Wdc65C02 => Box::new(super::cmos::Decimal(instruction)),
+  }
+}
+
+pub type DecodedInstruction<B> = Box<dyn Instruction<B>>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+  IllegalOpcode { opcode: u8, address: u16 },
+}
+
+impl std::fmt::Display for DecodeError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      DecodeError::IllegalOpcode { opcode, address } => {
+        write!(f, "Illegal opcode ${:02X} at ${:04X}", opcode, address)
+      }
+    }
+  }
+}
+
+impl std::error::Error for DecodeError {}
+
+pub fn interp<B: Bus>(
+  bus: &B,
+  pc: u16,
+  variant: Variant,
+  undocumented_opcodes: bool,
+) -> Result<DecodedInstruction<B>, DecodeError> {
+  let op = bus.peek(pc);
+  let byte = || bus.peek(pc.wrapping_add(1));
+  let word = || {
+    u16::from_le_bytes([
+      bus.peek(pc.wrapping_add(1)),
+      bus.peek(pc.wrapping_add(2)),
+    ])
+  };
+
+  if variant == Variant::Wdc65C02 {
+    if let Some(instruction) = super::cmos::interp(bus, pc) {
+      return Ok(instruction);
+    }
+  }
+
+  let instruction: DecodedInstruction<B> = match op {
+    0x69 => arithmetic(ADC(Immediate(byte())), variant),
+    0x65 => arithmetic(ADC(ZeroPage(byte())), variant),
+    0x75 => arithmetic(ADC(ZeroPageX(byte())), variant),
+    0x6D => arithmetic(ADC(Absolute(word())), variant),
+    0x7D => arithmetic(ADC(AbsoluteX(word())), variant),
+    0x79 => arithmetic(ADC(AbsoluteY(word())), variant),
+    0x61 => arithmetic(ADC(IndexedIndirect(byte())), variant),
+    0x71 => arithmetic(ADC(IndirectIndexed(byte())), variant),
+
+    0x29 => Box::new(AND(Immediate(byte()))),
+    0x25 => Box::new(AND(ZeroPage(byte()))),
+    0x35 => Box::new(AND(ZeroPageX(byte()))),
+    0x2D => Box::new(AND(Absolute(word()))),
+    0x3D => Box::new(AND(AbsoluteX(word()))),
+    0x39 => Box::new(AND(AbsoluteY(word()))),
+    0x21 => Box::new(AND(IndexedIndirect(byte()))),
+    0x31 => Box::new(AND(IndirectIndexed(byte()))),
 
     0x0A => Box::new(ASL(Accumulator)),
-    0x06 => Box::new(ASL(ZeroPage(memory.absolute(pc + 1)))),
-    0x16 => Box::new(ASL(ZeroPageX(memory.absolute(pc + 1)))),
-    0x0E => Box::new(ASL(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0x1E => Box::new(ASL(AbsoluteX(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-
-    0x24 => Box::new(BIT(ZeroPage(memory.absolute(pc + 1)))),
-    0x2C => Box::new(BIT(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-
-    0x10 => Box::new(BPL(memory.absolute(pc + 1) as i8)),
-    0x30 => Box::new(BMI(memory.absolute(pc + 1) as i8)),
-    0x50 => Box::new(BVC(memory.absolute(pc + 1) as i8)),
-    0x70 => Box::new(BVS(memory.absolute(pc + 1) as i8)),
-    0x90 => Box::new(BCC(memory.absolute(pc + 1) as i8)),
-    0xB0 => Box::new(BCS(memory.absolute(pc + 1) as i8)),
-    0xD0 => Box::new(BNE(memory.absolute(pc + 1) as i8)),
-    0xF0 => Box::new(BEQ(memory.absolute(pc + 1) as i8)),
+    0x06 => Box::new(ASL(ZeroPage(byte()))),
+    0x16 => Box::new(ASL(ZeroPageX(byte()))),
+    0x0E => Box::new(ASL(Absolute(word()))),
+    0x1E => Box::new(ASL(AbsoluteX(word()))),
+
+    0x24 => Box::new(BIT(ZeroPage(byte()))),
+    0x2C => Box::new(BIT(Absolute(word()))),
+
+    0x10 => Box::new(BPL(byte() as i8)),
+    0x30 => Box::new(BMI(byte() as i8)),
+    0x50 => Box::new(BVC(byte() as i8)),
+    0x70 => Box::new(BVS(byte() as i8)),
+    0x90 => Box::new(BCC(byte() as i8)),
+    0xB0 => Box::new(BCS(byte() as i8)),
+    0xD0 => Box::new(BNE(byte() as i8)),
+    0xF0 => Box::new(BEQ(byte() as i8)),
 
     0x00 => Box::new(BRK),
 
-    0xC9 => Box::new(CMP(Immediate(memory.absolute(pc + 1) as i8))),
-    0xC5 => Box::new(CMP(ZeroPage(memory.absolute(pc + 1)))),
-    0xD5 => Box::new(CMP(ZeroPageX(memory.absolute(pc + 1)))),
-    0xCD => Box::new(CMP(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0xDD => Box::new(CMP(AbsoluteX(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0xD9 => Box::new(CMP(AbsoluteY(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0xC1 => Box::new(CMP(IndexedIndirect(memory.absolute(pc + 1)))),
-    0xD1 => Box::new(CMP(IndirectIndexed(memory.absolute(pc + 1)))),
-
-    0xE0 => Box::new(CPX(Immediate(memory.absolute(pc + 1) as i8))),
-    0xE4 => Box::new(CPX(ZeroPage(memory.absolute(pc + 1)))),
-    0xEC => Box::new(CPX(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-
-    0xC0 => Box::new(CPY(Immediate(memory.absolute(pc + 1) as i8))),
-    0xC4 => Box::new(CPY(ZeroPage(memory.absolute(pc + 1)))),
-    0xCC => Box::new(CPY(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-
-    0xC6 => Box::new(DEC(ZeroPage(memory.absolute(pc + 1)))),
-    0xD6 => Box::new(DEC(ZeroPageX(memory.absolute(pc + 1)))),
-    0xCE => Box::new(DEC(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0xDE => Box::new(DEC(AbsoluteX(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-
-    0x49 => Box::new(EOR(Immediate(memory.absolute(pc + 1) as i8))),
-    0x45 => Box::new(EOR(ZeroPage(memory.absolute(pc + 1)))),
-    0x55 => Box::new(EOR(ZeroPageX(memory.absolute(pc + 1)))),
-    0x4D => Box::new(EOR(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0x5D => Box::new(EOR(AbsoluteX(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0x59 => Box::new(EOR(AbsoluteY(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0x41 => Box::new(EOR(IndexedIndirect(memory.absolute(pc + 1)))),
-    0x51 => Box::new(EOR(IndirectIndexed(memory.absolute(pc + 1)))),
+    0xC9 => Box::new(CMP(Immediate(byte()))),
+    0xC5 => Box::new(CMP(ZeroPage(byte()))),
+    0xD5 => Box::new(CMP(ZeroPageX(byte()))),
+    0xCD => Box::new(CMP(Absolute(word()))),
+    0xDD => Box::new(CMP(AbsoluteX(word()))),
+    0xD9 => Box::new(CMP(AbsoluteY(word()))),
+    0xC1 => Box::new(CMP(IndexedIndirect(byte()))),
+    0xD1 => Box::new(CMP(IndirectIndexed(byte()))),
+
+    0xE0 => Box::new(CPX(Immediate(byte()))),
+    0xE4 => Box::new(CPX(ZeroPage(byte()))),
+    0xEC => Box::new(CPX(Absolute(word()))),
+
+    0xC0 => Box::new(CPY(Immediate(byte()))),
+    0xC4 => Box::new(CPY(ZeroPage(byte()))),
+    0xCC => Box::new(CPY(Absolute(word()))),
+
+    0xC6 => Box::new(DEC(ZeroPage(byte()))),
+    0xD6 => Box::new(DEC(ZeroPageX(byte()))),
+    0xCE => Box::new(DEC(Absolute(word()))),
+    0xDE => Box::new(DEC(AbsoluteX(word()))),
+
+    0x49 => Box::new(EOR(Immediate(byte()))),
+    0x45 => Box::new(EOR(ZeroPage(byte()))),
+    0x55 => Box::new(EOR(ZeroPageX(byte()))),
+    0x4D => Box::new(EOR(Absolute(word()))),
+    0x5D => Box::new(EOR(AbsoluteX(word()))),
+    0x59 => Box::new(EOR(AbsoluteY(word()))),
+    0x41 => Box::new(EOR(IndexedIndirect(byte()))),
+    0x51 => Box::new(EOR(IndirectIndexed(byte()))),
 
     0x18 => Box::new(CLC),
     0x38 => Box::new(SEC),
@@ -140,104 +144,53 @@ pub(crate) fn interp(memory: &Memory, pc: u16) -> Box<dyn Instruction> {
     0xD8 => Box::new(CLD),
     0xF8 => Box::new(SED),
 
-    0xE6 => Box::new(INC(ZeroPage(memory.absolute(pc + 1)))),
-    0xF6 => Box::new(INC(ZeroPageX(memory.absolute(pc + 1)))),
-    0xEE => Box::new(INC(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0xFE => Box::new(INC(AbsoluteX(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-
-    0x4C => Box::new(JMP(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0x6C => Box::new(JMP(Indirect(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-
-    0x20 => Box::new(JSR(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ]))),
-
-    0xA9 => Box::new(LDA(Immediate(memory.absolute(pc + 1) as i8))),
-    0xA5 => Box::new(LDA(ZeroPage(memory.absolute(pc + 1)))),
-    0xB5 => Box::new(LDA(ZeroPageX(memory.absolute(pc + 1)))),
-    0xAD => Box::new(LDA(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0xBD => Box::new(LDA(AbsoluteX(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0xB9 => Box::new(LDA(AbsoluteY(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0xA1 => Box::new(LDA(IndexedIndirect(memory.absolute(pc + 1)))),
-    0xB1 => Box::new(LDA(IndirectIndexed(memory.absolute(pc + 1)))),
-
-    0xA2 => Box::new(LDX(Immediate(memory.absolute(pc + 1) as i8))),
-    0xA6 => Box::new(LDX(ZeroPage(memory.absolute(pc + 1)))),
-    0xB6 => Box::new(LDX(ZeroPageY(memory.absolute(pc + 1)))),
-    0xAE => Box::new(LDX(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0xBE => Box::new(LDX(AbsoluteY(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-
-    0xA0 => Box::new(LDY(Immediate(memory.absolute(pc + 1) as i8))),
-    0xA4 => Box::new(LDY(ZeroPage(memory.absolute(pc + 1)))),
-    0xB4 => Box::new(LDY(ZeroPageX(memory.absolute(pc + 1)))),
-    0xAC => Box::new(LDY(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0xBC => Box::new(LDY(AbsoluteX(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
+    0xE6 => Box::new(INC(ZeroPage(byte()))),
+    0xF6 => Box::new(INC(ZeroPageX(byte()))),
+    0xEE => Box::new(INC(Absolute(word()))),
+    0xFE => Box::new(INC(AbsoluteX(word()))),
+
+    0x4C => Box::new(JMP(Absolute(word()))),
+    0x6C => Box::new(JMP(Indirect(word()))),
+
+    0x20 => Box::new(JSR(word())),
+
+    0xA9 => Box::new(LDA(Immediate(byte()))),
+    0xA5 => Box::new(LDA(ZeroPage(byte()))),
+    0xB5 => Box::new(LDA(ZeroPageX(byte()))),
+    0xAD => Box::new(LDA(Absolute(word()))),
+    0xBD => Box::new(LDA(AbsoluteX(word()))),
+    0xB9 => Box::new(LDA(AbsoluteY(word()))),
+    0xA1 => Box::new(LDA(IndexedIndirect(byte()))),
+    0xB1 => Box::new(LDA(IndirectIndexed(byte()))),
+
+    0xA2 => Box::new(LDX(Immediate(byte()))),
+    0xA6 => Box::new(LDX(ZeroPage(byte()))),
+    0xB6 => Box::new(LDX(ZeroPageY(byte()))),
+    0xAE => Box::new(LDX(Absolute(word()))),
+    0xBE => Box::new(LDX(AbsoluteY(word()))),
+
+    0xA0 => Box::new(LDY(Immediate(byte()))),
+    0xA4 => Box::new(LDY(ZeroPage(byte()))),
+    0xB4 => Box::new(LDY(ZeroPageX(byte()))),
+    0xAC => Box::new(LDY(Absolute(word()))),
+    0xBC => Box::new(LDY(AbsoluteX(word()))),
 
     0x4A => Box::new(LSR(Accumulator)),
-    0x46 => Box::new(LSR(ZeroPage(memory.absolute(pc + 1)))),
-    0x56 => Box::new(LSR(ZeroPageX(memory.absolute(pc + 1)))),
-    0x4E => Box::new(LSR(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0x5E => Box::new(LSR(AbsoluteX(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
+    0x46 => Box::new(LSR(ZeroPage(byte()))),
+    0x56 => Box::new(LSR(ZeroPageX(byte()))),
+    0x4E => Box::new(LSR(Absolute(word()))),
+    0x5E => Box::new(LSR(AbsoluteX(word()))),
 
     0xEA => Box::new(NOP),
 
-    0x09 => Box::new(ORA(Immediate(memory.absolute(pc + 1) as i8))),
-    0x05 => Box::new(ORA(ZeroPage(memory.absolute(pc + 1)))),
-    0x15 => Box::new(ORA(ZeroPageX(memory.absolute(pc + 1)))),
-    0x0D => Box::new(ORA(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0x1D => Box::new(ORA(AbsoluteX(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0x19 => Box::new(ORA(AbsoluteY(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0x01 => Box::new(ORA(IndexedIndirect(memory.absolute(pc + 1)))),
-    0x11 => Box::new(ORA(IndirectIndexed(memory.absolute(pc + 1)))),
+    0x09 => Box::new(ORA(Immediate(byte()))),
+    0x05 => Box::new(ORA(ZeroPage(byte()))),
+    0x15 => Box::new(ORA(ZeroPageX(byte()))),
+    0x0D => Box::new(ORA(Absolute(word()))),
+    0x1D => Box::new(ORA(AbsoluteX(word()))),
+    0x19 => Box::new(ORA(AbsoluteY(word()))),
+    0x01 => Box::new(ORA(IndexedIndirect(byte()))),
+    0x11 => Box::new(ORA(IndirectIndexed(byte()))),
 
     0xAA => Box::new(TAX),
     0x8A => Box::new(TXA),
@@ -249,66 +202,37 @@ pub(crate) fn interp(memory: &Memory, pc: u16) -> Box<dyn Instruction> {
     0xC8 => Box::new(INY),
 
     0x2A => Box::new(ROL(Accumulator)),
-    0x26 => Box::new(ROL(ZeroPage(memory.absolute(pc + 1)))),
-    0x36 => Box::new(ROL(ZeroPageX(memory.absolute(pc + 1)))),
-    0x2E => Box::new(ROL(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0x3E => Box::new(ROL(AbsoluteX(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
+    0x26 => Box::new(ROL(ZeroPage(byte()))),
+    0x36 => Box::new(ROL(ZeroPageX(byte()))),
+    0x2E => Box::new(ROL(Absolute(word()))),
+    0x3E => Box::new(ROL(AbsoluteX(word()))),
 
     0x6A => Box::new(ROR(Accumulator)),
-    0x66 => Box::new(ROR(ZeroPage(memory.absolute(pc + 1)))),
-    0x76 => Box::new(ROR(ZeroPageX(memory.absolute(pc + 1)))),
-    0x6E => Box::new(ROR(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0x7E => Box::new(ROR(AbsoluteX(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
+    0x66 => Box::new(ROR(ZeroPage(byte()))),
+    0x76 => Box::new(ROR(ZeroPageX(byte()))),
+    0x6E => Box::new(ROR(Absolute(word()))),
+    0x7E => Box::new(ROR(AbsoluteX(word()))),
 
     0x40 => Box::new(RTI),
 
     0x60 => Box::new(RTS),
 
-    0xE9 => Box::new(SBC(Immediate(memory.absolute(pc + 1) as i8))),
-    0xE5 => Box::new(SBC(ZeroPage(memory.absolute(pc + 1)))),
-    0xF5 => Box::new(SBC(ZeroPageX(memory.absolute(pc + 1)))),
-    0xED => Box::new(SBC(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0xFD => Box::new(SBC(AbsoluteX(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0xF9 => Box::new(SBC(AbsoluteY(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0xE1 => Box::new(SBC(IndexedIndirect(memory.absolute(pc + 1)))),
-    0xF1 => Box::new(SBC(IndirectIndexed(memory.absolute(pc + 1)))),
-
-    0x85 => Box::new(STA(ZeroPage(memory.absolute(pc + 1)))),
-    0x95 => Box::new(STA(ZeroPageX(memory.absolute(pc + 1)))),
-    0x8D => Box::new(STA(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0x9D => Box::new(STA(AbsoluteX(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0x99 => Box::new(STA(AbsoluteY(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    0x81 => Box::new(STA(IndexedIndirect(memory.absolute(pc + 1)))),
+    0xE9 => arithmetic(SBC(Immediate(byte())), variant),
+    0xE5 => arithmetic(SBC(ZeroPage(byte())), variant),
+    0xF5 => arithmetic(SBC(ZeroPageX(byte())), variant),
+    0xED => arithmetic(SBC(Absolute(word())), variant),
+    0xFD => arithmetic(SBC(AbsoluteX(word())), variant),
+    0xF9 => arithmetic(SBC(AbsoluteY(word())), variant),
+    0xE1 => arithmetic(SBC(IndexedIndirect(byte())), variant),
+    0xF1 => arithmetic(SBC(IndirectIndexed(byte())), variant),
+
+    0x85 => Box::new(STA(ZeroPage(byte()))),
+    0x95 => Box::new(STA(ZeroPageX(byte()))),
+    0x8D => Box::new(STA(Absolute(word()))),
+    0x9D => Box::new(STA(AbsoluteX(word()))),
+    0x99 => Box::new(STA(AbsoluteY(word()))),
+    0x81 => Box::new(STA(IndexedIndirect(byte()))),
+    0x91 => Box::new(STA(IndirectIndexed(byte()))),
 
     0x9A => Box::new(TXS),
     0xBA => Box::new(TSX),
@@ -317,19 +241,136 @@ pub(crate) fn interp(memory: &Memory, pc: u16) -> Box<dyn Instruction> {
     0x08 => Box::new(PHP),
     0x28 => Box::new(PLP),
 
-    0x86 => Box::new(STX(ZeroPage(memory.absolute(pc + 1)))),
-    0x96 => Box::new(STX(ZeroPageY(memory.absolute(pc + 1)))),
-    0x8E => Box::new(STX(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-
-    0x84 => Box::new(STY(ZeroPage(memory.absolute(pc + 1)))),
-    0x94 => Box::new(STY(ZeroPageX(memory.absolute(pc + 1)))),
-    0x8C => Box::new(STY(Absolute(u16::from_le_bytes([
-      memory.absolute(pc + 1),
-      memory.absolute(pc + 2),
-    ])))),
-    _ => panic!("Invalid instruction"),
+    0x86 => Box::new(STX(ZeroPage(byte()))),
+    0x96 => Box::new(STX(ZeroPageY(byte()))),
+    0x8E => Box::new(STX(Absolute(word()))),
+
+    0x84 => Box::new(STY(ZeroPage(byte()))),
+    0x94 => Box::new(STY(ZeroPageX(byte()))),
+    0x8C => Box::new(STY(Absolute(word()))),
+    _ => {
+      // The 65C02 reuses these opcodes, so they only exist on NMOS parts.
+      let undocumented = if undocumented_opcodes && variant != Variant::Wdc65C02
+      {
+        super::undocumented::interp(bus, pc, variant)
+      } else {
+        None
+      };
+      return undocumented.ok_or(DecodeError::IllegalOpcode {
+        opcode: op,
+        address: pc,
+      });
+    }
+  };
+
+  Ok(instruction)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::asm::assemble;
+  use crate::disasm::disassemble_one;
+  use crate::memory::Memory;
+
+  const VARIANTS: [Variant; 3] =
+    [Variant::Nmos6502, Variant::Ricoh2A03, Variant::Wdc65C02];
+  const ORIGIN: u16 = 0x0200;
+
+  fn memory(bytes: &[u8]) -> Memory {
+    let mut memory = Memory::new();
+    for (offset, byte) in bytes.iter().enumerate() {
+      memory.write(ORIGIN + offset as u16, *byte);
+    }
+    memory
+  }
+
+  // Operands that exercise zero, sign and page boundaries.
+  const OPERANDS: [[u8; 2]; 4] =
+    [[0x00, 0x00], [0x7F, 0x80], [0x80, 0xFF], [0xFF, 0x12]];
+
+  #[test]
+  fn render_inverts_interp_for_every_opcode() {
+    for &variant in &VARIANTS {
+      for &undocumented in &[false, true] {
+        for opcode in 0..=0xFF {
+          for operand in &OPERANDS {
+            let bytes = [opcode, operand[0], operand[1]];
+            let memory = memory(&bytes);
+            let instruction =
+              match interp(&memory, ORIGIN, variant, undocumented) {
+                Ok(instruction) => instruction,
+                Err(_) => continue,
+              };
+
+            let rendered = instruction.render();
+            assert!(
+              !rendered.is_empty() && rendered.len() <= 3,
+              "{:?} ${:02X} renders {} bytes",
+              variant,
+              opcode,
+              rendered.len()
+            );
+            assert_eq!(
+              rendered,
+              &bytes[..rendered.len()],
+              "{:?} ${:02X} does not round trip",
+              variant,
+              opcode
+            );
+          }
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn documented_opcodes_decode() {
+    let memory = |opcode| memory(&[opcode, 0, 0]);
+    let decoded = (0..=0xFF)
+      .filter(|&opcode| {
+        interp(&memory(opcode), ORIGIN, Variant::Nmos6502, false).is_ok()
+      })
+      .count();
+
+    assert_eq!(decoded, 151);
+  }
+
+  #[test]
+  fn undocumented_opcodes_decode() {
+    let memory = |opcode| memory(&[opcode, 0, 0]);
+    let illegal: Vec<u8> = (0..=0xFF)
+      .filter(|&opcode| {
+        interp(&memory(opcode), ORIGIN, Variant::Nmos6502, true).is_err()
+      })
+      .collect();
+
+    // Only the opcodes that jam the CPU or behave unpredictably remain.
+    assert_eq!(
+      illegal,
+      vec![
+        0x02, 0x12, 0x22, 0x32, 0x42, 0x52, 0x62, 0x72, 0x8B, 0x92, 0x93, 0x9B,
+        0x9C, 0x9E, 0x9F, 0xAB, 0xB2, 0xBB, 0xD2, 0xF2
+      ]
+    );
+  }
+
+  #[test]
+  fn assembler_inverts_disassembler_for_documented_opcodes() {
+    for opcode in 0..=0xFF {
+      for operand in &OPERANDS {
+        let bytes = [opcode, operand[0], operand[1]];
+        let memory = memory(&bytes);
+        if interp(&memory, ORIGIN, Variant::Nmos6502, false).is_err() {
+          continue;
+        }
+
+        let line = disassemble_one(&memory, ORIGIN, Variant::Nmos6502, false);
+        let source = format!(".org ${:04X}\n{}", ORIGIN, line.text);
+        let assembly = assemble(&source)
+          .unwrap_or_else(|error| panic!("{}: {}", line.text, error));
+        assert_eq!(assembly.bytes, line.bytes, "{}", line.text);
+      }
+    }
   }
 }
@@ -1,21 +1,38 @@
 #![allow(clippy::upper_case_acronyms)]
-// TODO: Handling of flags
 
+use crate::bus::Bus;
 use crate::memory::Memory;
-use crate::registers::Registers;
+use crate::registers::{Flags, Registers};
 
-pub(crate) mod addressing;
-pub(crate) mod interp;
+pub mod addressing;
+pub mod cmos;
+pub mod interp;
+pub mod undocumented;
 use addressing::*;
 
-pub(crate) trait Instruction: Renderable {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers);
+pub trait Instruction<B: Bus = Memory>: Renderable + Mnemonic {
+  // Returns the number of cycles taken.
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8;
 }
 
-pub(crate) trait Renderable {
+pub trait Renderable {
   fn render(&self) -> Vec<u8>;
 }
 
+// Assembly syntax. Operands are given the address of the instruction, as
+// branches show their target rather than the offset.
+pub trait Mnemonic {
+  fn mnemonic(&self) -> &'static str;
+  fn operand(&self, _pc: u16) -> String {
+    String::new()
+  }
+}
+
+// Base cycle count, before page crossing penalties.
+pub trait Cycles {
+  const CYCLES: u8;
+}
+
 #[macro_export]
 macro_rules! evaluate {
   ( $mem:expr, $reg:expr, $( $inst:expr ),* ) => {
@@ -27,7 +44,118 @@ macro_rules! evaluate {
   };
 }
 
-pub(crate) trait ADCAddressMode: AddressMode {}
+fn add(registers: &mut Registers, value: u8) {
+  if registers.flags.decimal_mode {
+    add_decimal(registers, value);
+  } else {
+    add_with_carry(registers, value);
+  }
+}
+
+fn subtract(registers: &mut Registers, value: u8) {
+  if registers.flags.decimal_mode {
+    subtract_decimal(registers, value);
+  } else {
+    // Subtraction is addition of the one's complement, borrowing through
+    // carry.
+    add_with_carry(registers, !value);
+  }
+}
+
+fn add_with_carry(registers: &mut Registers, value: u8) {
+  let acc = registers.acc.value;
+  let sum =
+    u16::from(acc) + u16::from(value) + u16::from(registers.flags.carry);
+  let result = sum as u8;
+
+  registers.flags.carry = sum > 0xFF;
+  // Overflow when both operands share a sign that the result does not.
+  registers.flags.overflow = ((acc ^ result) & (value ^ result) & 0x80) != 0;
+  registers.flags.update_zero_negative(result);
+
+  registers.acc.value = result;
+}
+
+// NMOS decimal addition. Z comes from the binary sum, while N and V are
+// taken before the high nibble is adjusted.
+fn add_decimal(registers: &mut Registers, value: u8) {
+  let acc = u16::from(registers.acc.value);
+  let value = u16::from(value);
+  let carry = u16::from(registers.flags.carry);
+
+  let mut low = (acc & 0x0F) + (value & 0x0F) + carry;
+  if low > 0x09 {
+    low += 0x06;
+  }
+  let mut result = (low & 0x0F) + (acc & 0xF0) + (value & 0xF0);
+  if low > 0x0F {
+    result += 0x10;
+  }
+
+  registers.flags.zero = ((acc + value + carry) & 0xFF) == 0;
+  registers.flags.negative = (result & 0x80) != 0;
+  registers.flags.overflow =
+    ((acc ^ result) & 0x80) != 0 && ((acc ^ value) & 0x80) == 0;
+
+  if (result & 0x1F0) > 0x90 {
+    result += 0x60;
+  }
+  registers.flags.carry = (result & 0xFF0) > 0xF0;
+
+  registers.acc.value = result as u8;
+}
+
+// NMOS decimal subtraction. Flags are identical to binary subtraction, only
+// the result is adjusted.
+fn subtract_decimal(registers: &mut Registers, value: u8) {
+  let acc = i16::from(registers.acc.value);
+  let borrow = i16::from(!registers.flags.carry);
+  add_with_carry(registers, !value);
+
+  let value = i16::from(value);
+  let low = (acc & 0x0F) - (value & 0x0F) - borrow;
+  let mut result = if (low & 0x10) != 0 {
+    ((low - 0x06) & 0x0F) | ((acc & 0xF0) - (value & 0xF0) - 0x10)
+  } else {
+    (low & 0x0F) | ((acc & 0xF0) - (value & 0xF0))
+  };
+  if (result & 0x100) != 0 {
+    result -= 0x60;
+  }
+
+  registers.acc.value = result as u8;
+}
+
+// The 2A03 keeps the D flag but lacks the BCD circuitry, so ADC and SBC
+// always operate in binary.
+pub struct Binary<I>(pub I);
+impl<B: Bus, I: Instruction<B>> Instruction<B> for Binary<I> {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let decimal_mode = registers.flags.decimal_mode;
+    registers.flags.decimal_mode = false;
+    let cycles = self.0.evaluate(bus, registers);
+    registers.flags.decimal_mode = decimal_mode;
+
+    cycles
+  }
+}
+
+impl<I: Mnemonic> Mnemonic for Binary<I> {
+  fn mnemonic(&self) -> &'static str {
+    self.0.mnemonic()
+  }
+  fn operand(&self, pc: u16) -> String {
+    self.0.operand(pc)
+  }
+}
+
+impl<I: Renderable> Renderable for Binary<I> {
+  fn render(&self) -> Vec<u8> {
+    self.0.render()
+  }
+}
+
+pub trait ADCAddressMode: AddressMode {}
 impl ADCAddressMode for Immediate {}
 impl ADCAddressMode for ZeroPage {}
 impl ADCAddressMode for ZeroPageX {}
@@ -37,18 +165,29 @@ impl ADCAddressMode for AbsoluteY {}
 impl ADCAddressMode for IndexedIndirect {}
 impl ADCAddressMode for IndirectIndexed {}
 
-pub(crate) struct ADC<T: ADCAddressMode>(pub T);
-impl<T: ADCAddressMode> Instruction for ADC<T>
+pub struct ADC<T: ADCAddressMode>(pub T);
+impl<B: Bus, T: ADCAddressMode> Instruction<B> for ADC<T>
 where
-  ADC<T>: Renderable,
+  ADC<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    let value = self.0.read(memory, registers);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let page_crossed = self.0.page_crossed(bus, registers);
+    let value = self.0.read(bus, registers);
 
-    let carry = if registers.flags.carry { 1 } else { 0 };
-    registers.acc.value += value + carry;
+    add(registers, value);
 
-    registers.pc.value += 1 + T::LENGTH;
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES + u8::from(page_crossed)
+  }
+}
+
+impl<T: ADCAddressMode> Mnemonic for ADC<T> {
+  fn mnemonic(&self) -> &'static str {
+    "ADC"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -58,49 +197,81 @@ impl Renderable for ADC<Immediate> {
   }
 }
 
+impl Cycles for ADC<Immediate> {
+  const CYCLES: u8 = 2;
+}
+
 impl Renderable for ADC<ZeroPage> {
   fn render(&self) -> Vec<u8> {
     [&[0x65], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ADC<ZeroPage> {
+  const CYCLES: u8 = 3;
+}
+
 impl Renderable for ADC<ZeroPageX> {
   fn render(&self) -> Vec<u8> {
     [&[0x75], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ADC<ZeroPageX> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for ADC<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0x6D], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ADC<Absolute> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for ADC<AbsoluteX> {
   fn render(&self) -> Vec<u8> {
     [&[0x7D], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ADC<AbsoluteX> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for ADC<AbsoluteY> {
   fn render(&self) -> Vec<u8> {
     [&[0x79], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ADC<AbsoluteY> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for ADC<IndexedIndirect> {
   fn render(&self) -> Vec<u8> {
     [&[0x61], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ADC<IndexedIndirect> {
+  const CYCLES: u8 = 6;
+}
+
 impl Renderable for ADC<IndirectIndexed> {
   fn render(&self) -> Vec<u8> {
     [&[0x71], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) trait ANDAddressMode: AddressMode {}
+impl Cycles for ADC<IndirectIndexed> {
+  const CYCLES: u8 = 5;
+}
+
+pub trait ANDAddressMode: AddressMode {}
 impl ANDAddressMode for Immediate {}
 impl ANDAddressMode for ZeroPage {}
 impl ANDAddressMode for ZeroPageX {}
@@ -110,17 +281,30 @@ impl ANDAddressMode for AbsoluteY {}
 impl ANDAddressMode for IndexedIndirect {}
 impl ANDAddressMode for IndirectIndexed {}
 
-pub(crate) struct AND<T: ANDAddressMode>(pub T);
-impl<T: ANDAddressMode> Instruction for AND<T>
+pub struct AND<T: ANDAddressMode>(pub T);
+impl<B: Bus, T: ANDAddressMode> Instruction<B> for AND<T>
 where
-  AND<T>: Renderable,
+  AND<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    let value = self.0.read(memory, registers);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let page_crossed = self.0.page_crossed(bus, registers);
+    let value = self.0.read(bus, registers);
 
     registers.acc.value &= value;
+    registers.flags.update_zero_negative(registers.acc.value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
 
-    registers.pc.value += 1 + T::LENGTH;
+    Self::CYCLES + u8::from(page_crossed)
+  }
+}
+
+impl<T: ANDAddressMode> Mnemonic for AND<T> {
+  fn mnemonic(&self) -> &'static str {
+    "AND"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -130,66 +314,133 @@ impl Renderable for AND<Immediate> {
   }
 }
 
+impl Cycles for AND<Immediate> {
+  const CYCLES: u8 = 2;
+}
+
 impl Renderable for AND<ZeroPage> {
   fn render(&self) -> Vec<u8> {
     [&[0x25], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for AND<ZeroPage> {
+  const CYCLES: u8 = 3;
+}
+
 impl Renderable for AND<ZeroPageX> {
   fn render(&self) -> Vec<u8> {
     [&[0x35], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for AND<ZeroPageX> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for AND<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0x2D], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for AND<Absolute> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for AND<AbsoluteX> {
   fn render(&self) -> Vec<u8> {
     [&[0x3D], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for AND<AbsoluteX> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for AND<AbsoluteY> {
   fn render(&self) -> Vec<u8> {
     [&[0x39], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for AND<AbsoluteY> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for AND<IndexedIndirect> {
   fn render(&self) -> Vec<u8> {
     [&[0x21], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for AND<IndexedIndirect> {
+  const CYCLES: u8 = 6;
+}
+
 impl Renderable for AND<IndirectIndexed> {
   fn render(&self) -> Vec<u8> {
     [&[0x31], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) trait ASLAddressMode: AddressMode {}
+impl Cycles for AND<IndirectIndexed> {
+  const CYCLES: u8 = 5;
+}
+
+// Shifts and rotates set carry from the bit shifted out.
+fn shift_left(registers: &mut Registers, value: u8) -> u8 {
+  registers.flags.carry = (value & 0x80) != 0;
+  value << 1
+}
+
+fn shift_right(registers: &mut Registers, value: u8) -> u8 {
+  registers.flags.carry = (value & 0x01) != 0;
+  value >> 1
+}
+
+// Rotates go through the carry flag, nine bits in total.
+fn rotate_left(registers: &mut Registers, value: u8) -> u8 {
+  let rotated = (value << 1) | u8::from(registers.flags.carry);
+  registers.flags.carry = (value & 0x80) != 0;
+  rotated
+}
+
+fn rotate_right(registers: &mut Registers, value: u8) -> u8 {
+  let rotated = (value >> 1) | (u8::from(registers.flags.carry) << 7);
+  registers.flags.carry = (value & 0x01) != 0;
+  rotated
+}
+
+pub trait ASLAddressMode: AddressMode {}
 impl ASLAddressMode for Accumulator {}
 impl ASLAddressMode for ZeroPage {}
 impl ASLAddressMode for ZeroPageX {}
 impl ASLAddressMode for Absolute {}
 impl ASLAddressMode for AbsoluteX {}
 
-pub(crate) struct ASL<T: ASLAddressMode>(pub T);
-impl<T: ASLAddressMode> Instruction for ASL<T>
+pub struct ASL<T: ASLAddressMode>(pub T);
+impl<B: Bus, T: ASLAddressMode> Instruction<B> for ASL<T>
 where
-  ASL<T>: Renderable,
+  ASL<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    let value = self.0.read(memory, registers);
-    let shifted = value << 1;
-    self.0.write(memory, registers, shifted);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let shifted = self.0.modify(bus, registers, shift_left);
+
+    registers.flags.update_zero_negative(shifted);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
 
-    registers.pc.value += 1 + T::LENGTH;
+impl<T: ASLAddressMode> Mnemonic for ASL<T> {
+  fn mnemonic(&self) -> &'static str {
+    "ASL"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -199,47 +450,84 @@ impl Renderable for ASL<Accumulator> {
   }
 }
 
+impl Cycles for ASL<Accumulator> {
+  const CYCLES: u8 = 2;
+}
+
 impl Renderable for ASL<ZeroPage> {
   fn render(&self) -> Vec<u8> {
     [&[0x06], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ASL<ZeroPage> {
+  const CYCLES: u8 = 5;
+}
+
 impl Renderable for ASL<ZeroPageX> {
   fn render(&self) -> Vec<u8> {
     [&[0x16], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ASL<ZeroPageX> {
+  const CYCLES: u8 = 6;
+}
+
 impl Renderable for ASL<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0x0E], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ASL<Absolute> {
+  const CYCLES: u8 = 6;
+}
+
 impl Renderable for ASL<AbsoluteX> {
   fn render(&self) -> Vec<u8> {
     [&[0x1E], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) trait BITAddressMode: AddressMode {}
+impl Cycles for ASL<AbsoluteX> {
+  const CYCLES: u8 = 7;
+}
+
+pub trait BITAddressMode: AddressMode {
+  // BIT #imm only sets Z, as there is no memory operand to copy N and V from.
+  const COPIES_BITS: bool = true;
+}
 impl BITAddressMode for ZeroPage {}
 impl BITAddressMode for Absolute {}
 
-pub(crate) struct BIT<T: BITAddressMode>(pub T);
-impl<T: BITAddressMode> Instruction for BIT<T>
+pub struct BIT<T: BITAddressMode>(pub T);
+impl<B: Bus, T: BITAddressMode> Instruction<B> for BIT<T>
 where
-  BIT<T>: Renderable,
+  BIT<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    let value = self.0.read(memory, registers);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let page_crossed = self.0.page_crossed(bus, registers);
+    let value = self.0.read(bus, registers);
 
     registers.flags.zero = (value & registers.acc.value) == 0;
-    registers.flags.negative = (value & (1 << 7)) != 0;
-    registers.flags.overflow = (value & (1 << 6)) != 0;
+    if T::COPIES_BITS {
+      registers.flags.negative = (value & (1 << 7)) != 0;
+      registers.flags.overflow = (value & (1 << 6)) != 0;
+    }
 
-    registers.pc.value += 1 + T::LENGTH;
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES + u8::from(page_crossed)
+  }
+}
+
+impl<T: BITAddressMode> Mnemonic for BIT<T> {
+  fn mnemonic(&self) -> &'static str {
+    "BIT"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -249,153 +537,309 @@ impl Renderable for BIT<ZeroPage> {
   }
 }
 
+impl Cycles for BIT<ZeroPage> {
+  const CYCLES: u8 = 3;
+}
+
 impl Renderable for BIT<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0x2C], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) struct BPL(pub i8);
-impl Instruction for BPL {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
-    registers.pc.value += 2;
+impl Cycles for BIT<Absolute> {
+  const CYCLES: u8 = 4;
+}
+
+// The stack lives in page one, growing downwards from $01FF.
+pub(crate) fn push<B: Bus>(bus: &mut B, registers: &mut Registers, value: u8) {
+  bus.absolute_write(u16::from_le_bytes([registers.sp.value, 0x01]), value);
+  registers.sp.value = registers.sp.value.wrapping_sub(1);
+}
+
+pub(crate) fn pull<B: Bus>(bus: &mut B, registers: &mut Registers) -> u8 {
+  registers.sp.value = registers.sp.value.wrapping_add(1);
+  bus.absolute(u16::from_le_bytes([registers.sp.value, 0x01]))
+}
+
+// Pulls, and JSR, spend a cycle reading the top of the stack before the
+// stack pointer moves.
+pub(crate) fn stack_dummy_read<B: Bus>(bus: &mut B, registers: &Registers) {
+  bus.dummy_read(u16::from_le_bytes([registers.sp.value, 0x01]));
+}
+
+// Taken branches cost one extra cycle, and one more if they cross a page.
+// Each of those cycles reads the next opcode, the second from the page the
+// branch started in.
+fn branch<B: Bus>(bus: &mut B, registers: &mut Registers, offset: i8) -> u8 {
+  let from = registers.pc.value;
+  registers.pc.value = from.wrapping_add(offset as u16);
+  bus.dummy_read(from);
+
+  if (from & 0xFF00) != (registers.pc.value & 0xFF00) {
+    bus.dummy_read((from & 0xFF00) | (registers.pc.value & 0x00FF));
+    4
+  } else {
+    3
+  }
+}
+
+pub struct BPL(pub i8);
+impl<B: Bus> Instruction<B> for BPL {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.pc.value = registers.pc.value.wrapping_add(2);
     if !registers.flags.negative {
-      let target = registers.pc.value as i16 + self.0 as i16;
-      registers.pc.value = target as u16;
+      branch(bus, registers, self.0)
+    } else {
+      2
     }
   }
 }
 
+impl Mnemonic for BPL {
+  fn mnemonic(&self) -> &'static str {
+    "BPL"
+  }
+  fn operand(&self, pc: u16) -> String {
+    format!("${:04X}", pc.wrapping_add(2).wrapping_add(self.0 as u16))
+  }
+}
+
 impl Renderable for BPL {
   fn render(&self) -> Vec<u8> {
-    vec![0x10]
+    vec![0x10, self.0 as u8]
   }
 }
 
-pub(crate) struct BMI(pub i8);
-impl Instruction for BMI {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
-    registers.pc.value += 2;
+pub struct BMI(pub i8);
+impl<B: Bus> Instruction<B> for BMI {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.pc.value = registers.pc.value.wrapping_add(2);
     if registers.flags.negative {
-      let target = registers.pc.value as i16 + self.0 as i16;
-      registers.pc.value = target as u16;
+      branch(bus, registers, self.0)
+    } else {
+      2
     }
   }
 }
 
+impl Mnemonic for BMI {
+  fn mnemonic(&self) -> &'static str {
+    "BMI"
+  }
+  fn operand(&self, pc: u16) -> String {
+    format!("${:04X}", pc.wrapping_add(2).wrapping_add(self.0 as u16))
+  }
+}
+
 impl Renderable for BMI {
   fn render(&self) -> Vec<u8> {
-    vec![0x30]
+    vec![0x30, self.0 as u8]
   }
 }
 
-pub(crate) struct BVC(pub i8);
-impl Instruction for BVC {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
-    registers.pc.value += 2;
+pub struct BVC(pub i8);
+impl<B: Bus> Instruction<B> for BVC {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.pc.value = registers.pc.value.wrapping_add(2);
     if !registers.flags.overflow {
-      let target = registers.pc.value as i16 + self.0 as i16;
-      registers.pc.value = target as u16;
+      branch(bus, registers, self.0)
+    } else {
+      2
     }
   }
 }
 
+impl Mnemonic for BVC {
+  fn mnemonic(&self) -> &'static str {
+    "BVC"
+  }
+  fn operand(&self, pc: u16) -> String {
+    format!("${:04X}", pc.wrapping_add(2).wrapping_add(self.0 as u16))
+  }
+}
+
 impl Renderable for BVC {
   fn render(&self) -> Vec<u8> {
-    vec![0x50]
+    vec![0x50, self.0 as u8]
   }
 }
 
-pub(crate) struct BVS(pub i8);
-impl Instruction for BVS {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
-    registers.pc.value += 2;
+pub struct BVS(pub i8);
+impl<B: Bus> Instruction<B> for BVS {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.pc.value = registers.pc.value.wrapping_add(2);
     if registers.flags.overflow {
-      let target = registers.pc.value as i16 + self.0 as i16;
-      registers.pc.value = target as u16;
+      branch(bus, registers, self.0)
+    } else {
+      2
     }
   }
 }
 
+impl Mnemonic for BVS {
+  fn mnemonic(&self) -> &'static str {
+    "BVS"
+  }
+  fn operand(&self, pc: u16) -> String {
+    format!("${:04X}", pc.wrapping_add(2).wrapping_add(self.0 as u16))
+  }
+}
+
 impl Renderable for BVS {
   fn render(&self) -> Vec<u8> {
-    vec![0x70]
+    vec![0x70, self.0 as u8]
   }
 }
 
-pub(crate) struct BCC(pub i8);
-impl Instruction for BCC {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
-    registers.pc.value += 2;
-    if registers.flags.carry {
-      let target = registers.pc.value as i16 + self.0 as i16;
-      registers.pc.value = target as u16;
+pub struct BCC(pub i8);
+impl<B: Bus> Instruction<B> for BCC {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.pc.value = registers.pc.value.wrapping_add(2);
+    if !registers.flags.carry {
+      branch(bus, registers, self.0)
+    } else {
+      2
     }
   }
 }
 
+impl Mnemonic for BCC {
+  fn mnemonic(&self) -> &'static str {
+    "BCC"
+  }
+  fn operand(&self, pc: u16) -> String {
+    format!("${:04X}", pc.wrapping_add(2).wrapping_add(self.0 as u16))
+  }
+}
+
 impl Renderable for BCC {
   fn render(&self) -> Vec<u8> {
-    vec![0x90]
+    vec![0x90, self.0 as u8]
   }
 }
 
-pub(crate) struct BCS(pub i8);
-impl Instruction for BCS {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
-    registers.pc.value += 2;
-    if !registers.flags.carry {
-      let target = registers.pc.value as i16 + self.0 as i16;
-      registers.pc.value = target as u16;
+pub struct BCS(pub i8);
+impl<B: Bus> Instruction<B> for BCS {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.pc.value = registers.pc.value.wrapping_add(2);
+    if registers.flags.carry {
+      branch(bus, registers, self.0)
+    } else {
+      2
     }
   }
 }
 
+impl Mnemonic for BCS {
+  fn mnemonic(&self) -> &'static str {
+    "BCS"
+  }
+  fn operand(&self, pc: u16) -> String {
+    format!("${:04X}", pc.wrapping_add(2).wrapping_add(self.0 as u16))
+  }
+}
+
 impl Renderable for BCS {
   fn render(&self) -> Vec<u8> {
-    vec![0xB0]
+    vec![0xB0, self.0 as u8]
   }
 }
 
-pub(crate) struct BNE(pub i8);
-impl Instruction for BNE {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
-    registers.pc.value += 2;
+pub struct BNE(pub i8);
+impl<B: Bus> Instruction<B> for BNE {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.pc.value = registers.pc.value.wrapping_add(2);
     if !registers.flags.zero {
-      let target = registers.pc.value as i16 + self.0 as i16;
-      registers.pc.value = target as u16;
+      branch(bus, registers, self.0)
+    } else {
+      2
     }
   }
 }
 
+impl Mnemonic for BNE {
+  fn mnemonic(&self) -> &'static str {
+    "BNE"
+  }
+  fn operand(&self, pc: u16) -> String {
+    format!("${:04X}", pc.wrapping_add(2).wrapping_add(self.0 as u16))
+  }
+}
+
 impl Renderable for BNE {
   fn render(&self) -> Vec<u8> {
-    vec![0xD0]
+    vec![0xD0, self.0 as u8]
   }
 }
 
-pub(crate) struct BEQ(pub i8);
-impl Instruction for BEQ {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
-    registers.pc.value += 2;
+pub struct BEQ(pub i8);
+impl<B: Bus> Instruction<B> for BEQ {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.pc.value = registers.pc.value.wrapping_add(2);
     if registers.flags.zero {
-      let target = registers.pc.value as i16 + self.0 as i16;
-      registers.pc.value = target as u16;
+      branch(bus, registers, self.0)
+    } else {
+      2
     }
   }
 }
 
+impl Mnemonic for BEQ {
+  fn mnemonic(&self) -> &'static str {
+    "BEQ"
+  }
+  fn operand(&self, pc: u16) -> String {
+    format!("${:04X}", pc.wrapping_add(2).wrapping_add(self.0 as u16))
+  }
+}
+
 impl Renderable for BEQ {
   fn render(&self) -> Vec<u8> {
-    vec![0xF0]
+    vec![0xF0, self.0 as u8]
+  }
+}
+
+pub const NMI_VECTOR: u16 = 0xFFFA;
+pub const RESET_VECTOR: u16 = 0xFFFC;
+pub const IRQ_VECTOR: u16 = 0xFFFE;
+
+// Shared by BRK and the hardware interrupt lines: push the return address and
+// status, mask further IRQs and jump through the vector.
+pub(crate) fn interrupt<B: Bus>(
+  bus: &mut B,
+  registers: &mut Registers,
+  vector: u16,
+  brk: bool,
+) {
+  let [low, high] = registers.pc.value.to_le_bytes();
+  push(bus, registers, high);
+  push(bus, registers, low);
+
+  let status = registers.flags.to_byte(brk);
+  push(bus, registers, status);
+
+  registers.flags.interrupt_disable = true;
+  registers.pc.value = u16::from_le_bytes([
+    bus.absolute(vector),
+    bus.absolute(vector.wrapping_add(1)),
+  ]);
+}
+
+pub struct BRK;
+impl<B: Bus> Instruction<B> for BRK {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    // The byte after BRK is padding, skipped on return.
+    registers.pc.value = registers.pc.value.wrapping_add(2);
+    interrupt(bus, registers, IRQ_VECTOR, true);
+
+    7
   }
 }
 
-pub(crate) struct BRK;
-impl Instruction for BRK {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
-    // TODO: Interupt
-    registers.pc.value += 1;
+impl Mnemonic for BRK {
+  fn mnemonic(&self) -> &'static str {
+    "BRK"
   }
 }
 
@@ -405,7 +849,15 @@ impl Renderable for BRK {
   }
 }
 
-pub(crate) trait CMPAddressMode: AddressMode {}
+// Unsigned comparison, setting flags as if subtracting without borrow.
+fn compare(registers: &mut Registers, register: u8, value: u8) {
+  registers.flags.carry = register >= value;
+  registers
+    .flags
+    .update_zero_negative(register.wrapping_sub(value));
+}
+
+pub trait CMPAddressMode: AddressMode {}
 impl CMPAddressMode for Immediate {}
 impl CMPAddressMode for ZeroPage {}
 impl CMPAddressMode for ZeroPageX {}
@@ -415,21 +867,29 @@ impl CMPAddressMode for AbsoluteY {}
 impl CMPAddressMode for IndexedIndirect {}
 impl CMPAddressMode for IndirectIndexed {}
 
-pub(crate) struct CMP<T: CMPAddressMode>(pub T);
-impl<T: CMPAddressMode> Instruction for CMP<T>
+pub struct CMP<T: CMPAddressMode>(pub T);
+impl<B: Bus, T: CMPAddressMode> Instruction<B> for CMP<T>
 where
-  CMP<T>: Renderable,
+  CMP<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    let value = self.0.read(memory, registers);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let page_crossed = self.0.page_crossed(bus, registers);
+    let value = self.0.read(bus, registers);
 
-    let test = registers.acc.value - value;
+    compare(registers, registers.acc.value, value);
 
-    registers.flags.carry = test >= 0;
-    registers.flags.zero = test == 0;
-    registers.flags.negative = test < 0;
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
 
-    registers.pc.value += 1 + T::LENGTH;
+    Self::CYCLES + u8::from(page_crossed)
+  }
+}
+
+impl<T: CMPAddressMode> Mnemonic for CMP<T> {
+  fn mnemonic(&self) -> &'static str {
+    "CMP"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -439,68 +899,107 @@ impl Renderable for CMP<Immediate> {
   }
 }
 
+impl Cycles for CMP<Immediate> {
+  const CYCLES: u8 = 2;
+}
+
 impl Renderable for CMP<ZeroPage> {
   fn render(&self) -> Vec<u8> {
     [&[0xC5], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for CMP<ZeroPage> {
+  const CYCLES: u8 = 3;
+}
+
 impl Renderable for CMP<ZeroPageX> {
   fn render(&self) -> Vec<u8> {
     [&[0xD5], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for CMP<ZeroPageX> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for CMP<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0xCD], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for CMP<Absolute> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for CMP<AbsoluteX> {
   fn render(&self) -> Vec<u8> {
     [&[0xDD], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for CMP<AbsoluteX> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for CMP<AbsoluteY> {
   fn render(&self) -> Vec<u8> {
     [&[0xD9], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for CMP<AbsoluteY> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for CMP<IndexedIndirect> {
   fn render(&self) -> Vec<u8> {
     [&[0xC1], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for CMP<IndexedIndirect> {
+  const CYCLES: u8 = 6;
+}
+
 impl Renderable for CMP<IndirectIndexed> {
   fn render(&self) -> Vec<u8> {
     [&[0xD1], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) trait CPXAddressMode: AddressMode {}
+impl Cycles for CMP<IndirectIndexed> {
+  const CYCLES: u8 = 5;
+}
+
+pub trait CPXAddressMode: AddressMode {}
 impl CPXAddressMode for Immediate {}
 impl CPXAddressMode for ZeroPage {}
 impl CPXAddressMode for Absolute {}
 
-pub(crate) struct CPX<T: CPXAddressMode>(pub T);
-impl<T: CPXAddressMode> Instruction for CPX<T>
+pub struct CPX<T: CPXAddressMode>(pub T);
+impl<B: Bus, T: CPXAddressMode> Instruction<B> for CPX<T>
 where
-  CPX<T>: Renderable,
+  CPX<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    let value = self.0.read(memory, registers);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let value = self.0.read(bus, registers);
 
-    let test = registers.x.value - value;
+    compare(registers, registers.x.value, value);
 
-    registers.flags.carry = test >= 0;
-    registers.flags.zero = test == 0;
-    registers.flags.negative = test < 0;
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
 
-    registers.pc.value += 1 + T::LENGTH;
+    Self::CYCLES
+  }
+}
+
+impl<T: CPXAddressMode> Mnemonic for CPX<T> {
+  fn mnemonic(&self) -> &'static str {
+    "CPX"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -510,38 +1009,57 @@ impl Renderable for CPX<Immediate> {
   }
 }
 
+impl Cycles for CPX<Immediate> {
+  const CYCLES: u8 = 2;
+}
+
 impl Renderable for CPX<ZeroPage> {
   fn render(&self) -> Vec<u8> {
     [&[0xE4], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for CPX<ZeroPage> {
+  const CYCLES: u8 = 3;
+}
+
 impl Renderable for CPX<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0xEC], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) trait CPYAddressMode: AddressMode {}
+impl Cycles for CPX<Absolute> {
+  const CYCLES: u8 = 4;
+}
+
+pub trait CPYAddressMode: AddressMode {}
 impl CPYAddressMode for Immediate {}
 impl CPYAddressMode for ZeroPage {}
 impl CPYAddressMode for Absolute {}
 
-pub(crate) struct CPY<T: CPYAddressMode>(pub T);
-impl<T: CPYAddressMode> Instruction for CPY<T>
+pub struct CPY<T: CPYAddressMode>(pub T);
+impl<B: Bus, T: CPYAddressMode> Instruction<B> for CPY<T>
 where
-  CPY<T>: Renderable,
+  CPY<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    let value = self.0.read(memory, registers);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let value = self.0.read(bus, registers);
 
-    let test = registers.y.value - value;
+    compare(registers, registers.y.value, value);
 
-    registers.flags.carry = test >= 0;
-    registers.flags.zero = test == 0;
-    registers.flags.negative = test < 0;
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
 
-    registers.pc.value += 1 + T::LENGTH;
+impl<T: CPYAddressMode> Mnemonic for CPY<T> {
+  fn mnemonic(&self) -> &'static str {
+    "CPY"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -551,34 +1069,59 @@ impl Renderable for CPY<Immediate> {
   }
 }
 
+impl Cycles for CPY<Immediate> {
+  const CYCLES: u8 = 2;
+}
+
 impl Renderable for CPY<ZeroPage> {
   fn render(&self) -> Vec<u8> {
     [&[0xC4], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for CPY<ZeroPage> {
+  const CYCLES: u8 = 3;
+}
+
 impl Renderable for CPY<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0xCC], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) trait DECAddressMode: AddressMode {}
+impl Cycles for CPY<Absolute> {
+  const CYCLES: u8 = 4;
+}
+
+pub trait DECAddressMode: AddressMode {}
 impl DECAddressMode for ZeroPage {}
 impl DECAddressMode for ZeroPageX {}
 impl DECAddressMode for Absolute {}
 impl DECAddressMode for AbsoluteX {}
 
-pub(crate) struct DEC<T: DECAddressMode>(pub T);
-impl<T: DECAddressMode> Instruction for DEC<T>
+pub struct DEC<T: DECAddressMode>(pub T);
+impl<B: Bus, T: DECAddressMode> Instruction<B> for DEC<T>
 where
-  DEC<T>: Renderable,
+  DEC<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    let value = self.0.read(memory, registers);
-    self.0.write(memory, registers, value - 1);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let value = self
+      .0
+      .modify(bus, registers, |_, value| value.wrapping_sub(1));
+    registers.flags.update_zero_negative(value);
 
-    registers.pc.value += 1 + T::LENGTH;
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
+
+impl<T: DECAddressMode> Mnemonic for DEC<T> {
+  fn mnemonic(&self) -> &'static str {
+    "DEC"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -588,25 +1131,41 @@ impl Renderable for DEC<ZeroPage> {
   }
 }
 
+impl Cycles for DEC<ZeroPage> {
+  const CYCLES: u8 = 5;
+}
+
 impl Renderable for DEC<ZeroPageX> {
   fn render(&self) -> Vec<u8> {
     [&[0xD6], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for DEC<ZeroPageX> {
+  const CYCLES: u8 = 6;
+}
+
 impl Renderable for DEC<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0xCE], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for DEC<Absolute> {
+  const CYCLES: u8 = 6;
+}
+
 impl Renderable for DEC<AbsoluteX> {
   fn render(&self) -> Vec<u8> {
     [&[0xDE], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) trait EORAddressMode: AddressMode {}
+impl Cycles for DEC<AbsoluteX> {
+  const CYCLES: u8 = 7;
+}
+
+pub trait EORAddressMode: AddressMode {}
 impl EORAddressMode for Immediate {}
 impl EORAddressMode for ZeroPage {}
 impl EORAddressMode for ZeroPageX {}
@@ -616,19 +1175,30 @@ impl EORAddressMode for AbsoluteY {}
 impl EORAddressMode for IndexedIndirect {}
 impl EORAddressMode for IndirectIndexed {}
 
-pub(crate) struct EOR<T: EORAddressMode>(pub T);
-impl<T: EORAddressMode> Instruction for EOR<T>
+pub struct EOR<T: EORAddressMode>(pub T);
+impl<B: Bus, T: EORAddressMode> Instruction<B> for EOR<T>
 where
-  EOR<T>: Renderable,
+  EOR<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    let value = self.0.read(memory, registers);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let page_crossed = self.0.page_crossed(bus, registers);
+    let value = self.0.read(bus, registers);
 
-    let result = value ^ registers.acc.value;
+    registers.acc.value ^= value;
+    registers.flags.update_zero_negative(registers.acc.value);
 
-    self.0.write(memory, registers, result);
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
 
-    registers.pc.value += 1 + T::LENGTH;
+    Self::CYCLES + u8::from(page_crossed)
+  }
+}
+
+impl<T: EORAddressMode> Mnemonic for EOR<T> {
+  fn mnemonic(&self) -> &'static str {
+    "EOR"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -638,54 +1208,94 @@ impl Renderable for EOR<Immediate> {
   }
 }
 
+impl Cycles for EOR<Immediate> {
+  const CYCLES: u8 = 2;
+}
+
 impl Renderable for EOR<ZeroPage> {
   fn render(&self) -> Vec<u8> {
     [&[0x45], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for EOR<ZeroPage> {
+  const CYCLES: u8 = 3;
+}
+
 impl Renderable for EOR<ZeroPageX> {
   fn render(&self) -> Vec<u8> {
     [&[0x55], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for EOR<ZeroPageX> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for EOR<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0x4D], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for EOR<Absolute> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for EOR<AbsoluteX> {
   fn render(&self) -> Vec<u8> {
     [&[0x5D], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for EOR<AbsoluteX> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for EOR<AbsoluteY> {
   fn render(&self) -> Vec<u8> {
     [&[0x59], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for EOR<AbsoluteY> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for EOR<IndexedIndirect> {
   fn render(&self) -> Vec<u8> {
     [&[0x41], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for EOR<IndexedIndirect> {
+  const CYCLES: u8 = 6;
+}
+
 impl Renderable for EOR<IndirectIndexed> {
   fn render(&self) -> Vec<u8> {
     [&[0x51], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) struct CLC;
-impl Instruction for CLC {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
+impl Cycles for EOR<IndirectIndexed> {
+  const CYCLES: u8 = 5;
+}
+
+pub struct CLC;
+impl<B: Bus> Instruction<B> for CLC {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
     registers.flags.carry = false;
 
-    registers.pc.value += 1;
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    2
+  }
+}
+
+impl Mnemonic for CLC {
+  fn mnemonic(&self) -> &'static str {
+    "CLC"
   }
 }
 
@@ -695,12 +1305,20 @@ impl Renderable for CLC {
   }
 }
 
-pub(crate) struct SEC;
-impl Instruction for SEC {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
+pub struct SEC;
+impl<B: Bus> Instruction<B> for SEC {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
     registers.flags.carry = true;
 
-    registers.pc.value += 1;
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    2
+  }
+}
+
+impl Mnemonic for SEC {
+  fn mnemonic(&self) -> &'static str {
+    "SEC"
   }
 }
 
@@ -710,12 +1328,20 @@ impl Renderable for SEC {
   }
 }
 
-pub(crate) struct CLI;
-impl Instruction for CLI {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
+pub struct CLI;
+impl<B: Bus> Instruction<B> for CLI {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
     registers.flags.interrupt_disable = false;
 
-    registers.pc.value += 1;
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    2
+  }
+}
+
+impl Mnemonic for CLI {
+  fn mnemonic(&self) -> &'static str {
+    "CLI"
   }
 }
 
@@ -725,12 +1351,20 @@ impl Renderable for CLI {
   }
 }
 
-pub(crate) struct SEI;
-impl Instruction for SEI {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
+pub struct SEI;
+impl<B: Bus> Instruction<B> for SEI {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
     registers.flags.interrupt_disable = true;
 
-    registers.pc.value += 1;
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    2
+  }
+}
+
+impl Mnemonic for SEI {
+  fn mnemonic(&self) -> &'static str {
+    "SEI"
   }
 }
 
@@ -740,12 +1374,20 @@ impl Renderable for SEI {
   }
 }
 
-pub(crate) struct CLV;
-impl Instruction for CLV {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
+pub struct CLV;
+impl<B: Bus> Instruction<B> for CLV {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
     registers.flags.overflow = false;
 
-    registers.pc.value += 1;
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    2
+  }
+}
+
+impl Mnemonic for CLV {
+  fn mnemonic(&self) -> &'static str {
+    "CLV"
   }
 }
 
@@ -755,12 +1397,20 @@ impl Renderable for CLV {
   }
 }
 
-pub(crate) struct CLD;
-impl Instruction for CLD {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
+pub struct CLD;
+impl<B: Bus> Instruction<B> for CLD {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
     registers.flags.decimal_mode = false;
 
-    registers.pc.value += 1;
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    2
+  }
+}
+
+impl Mnemonic for CLD {
+  fn mnemonic(&self) -> &'static str {
+    "CLD"
   }
 }
 
@@ -770,12 +1420,20 @@ impl Renderable for CLD {
   }
 }
 
-pub(crate) struct SED;
-impl Instruction for SED {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
+pub struct SED;
+impl<B: Bus> Instruction<B> for SED {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
     registers.flags.decimal_mode = true;
 
-    registers.pc.value += 1;
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    2
+  }
+}
+
+impl Mnemonic for SED {
+  fn mnemonic(&self) -> &'static str {
+    "SED"
   }
 }
 
@@ -785,22 +1443,35 @@ impl Renderable for SED {
   }
 }
 
-pub(crate) trait INCAddressMode: AddressMode {}
+pub trait INCAddressMode: AddressMode {}
 impl INCAddressMode for ZeroPage {}
 impl INCAddressMode for ZeroPageX {}
 impl INCAddressMode for Absolute {}
 impl INCAddressMode for AbsoluteX {}
 
-pub(crate) struct INC<T: INCAddressMode>(pub T);
-impl<T: INCAddressMode> Instruction for INC<T>
+pub struct INC<T: INCAddressMode>(pub T);
+impl<B: Bus, T: INCAddressMode> Instruction<B> for INC<T>
 where
-  INC<T>: Renderable,
+  INC<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    let value = self.0.read(memory, registers);
-    self.0.write(memory, registers, value + 1);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let value = self
+      .0
+      .modify(bus, registers, |_, value| value.wrapping_add(1));
+    registers.flags.update_zero_negative(value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
 
-    registers.pc.value += 1;
+    Self::CYCLES
+  }
+}
+
+impl<T: INCAddressMode> Mnemonic for INC<T> {
+  fn mnemonic(&self) -> &'static str {
+    "INC"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -810,35 +1481,62 @@ impl Renderable for INC<ZeroPage> {
   }
 }
 
+impl Cycles for INC<ZeroPage> {
+  const CYCLES: u8 = 5;
+}
+
 impl Renderable for INC<ZeroPageX> {
   fn render(&self) -> Vec<u8> {
     [&[0xF6], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for INC<ZeroPageX> {
+  const CYCLES: u8 = 6;
+}
+
 impl Renderable for INC<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0xEE], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for INC<Absolute> {
+  const CYCLES: u8 = 6;
+}
+
 impl Renderable for INC<AbsoluteX> {
   fn render(&self) -> Vec<u8> {
     [&[0xFE], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) trait JMPAddressMode: JumpMode {}
+impl Cycles for INC<AbsoluteX> {
+  const CYCLES: u8 = 7;
+}
+
+pub trait JMPAddressMode: JumpMode {}
 impl JMPAddressMode for Absolute {}
 impl JMPAddressMode for Indirect {}
 
-pub(crate) struct JMP<T: JMPAddressMode>(pub T);
-impl<T: JMPAddressMode> Instruction for JMP<T>
+pub struct JMP<T: JMPAddressMode>(pub T);
+impl<B: Bus, T: JMPAddressMode> Instruction<B> for JMP<T>
 where
-  JMP<T>: Renderable,
+  JMP<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    registers.pc.value = self.0.dest(memory);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.pc.value = self.0.dest(bus, registers);
+
+    Self::CYCLES
+  }
+}
+
+impl<T: JMPAddressMode> Mnemonic for JMP<T> {
+  fn mnemonic(&self) -> &'static str {
+    "JMP"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -848,35 +1546,58 @@ impl Renderable for JMP<Absolute> {
   }
 }
 
+impl Cycles for JMP<Absolute> {
+  const CYCLES: u8 = 3;
+}
+
 impl Renderable for JMP<Indirect> {
   fn render(&self) -> Vec<u8> {
     [&[0x6C], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) struct JSR(u16);
-impl Instruction for JSR {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    let addr = (registers.pc.value + 2).to_le_bytes();
-    memory
-      .absolute_write(u16::from_le_bytes([0x01, registers.sp.value]), addr[0]);
-    memory.absolute_write(
-      u16::from_le_bytes([0x01, registers.sp.value - 1]),
-      addr[1],
-    );
-    registers.sp.value -= 2;
+impl Cycles for JMP<Indirect> {
+  const CYCLES: u8 = 5;
+}
 
-    registers.pc.value = self.0;
+pub const JSR_OPCODE: u8 = 0x20;
+
+pub struct JSR(pub u16);
+impl<B: Bus> Instruction<B> for JSR {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    // The return address pushed is that of the last byte of the instruction,
+    // which is only read once it has been pushed, so a push can change where
+    // the jump goes.
+    let last = registers.pc.value.wrapping_add(2);
+    let [low, high] = last.to_le_bytes();
+    stack_dummy_read(bus, registers);
+    push(bus, registers, high);
+    push(bus, registers, low);
+
+    let [target, decoded] = self.0.to_le_bytes();
+    let high = bus.deferred_read(last).unwrap_or(decoded);
+    registers.pc.value = u16::from_le_bytes([target, high]);
+
+    6
+  }
+}
+
+impl Mnemonic for JSR {
+  fn mnemonic(&self) -> &'static str {
+    "JSR"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    format!("${:04X}", self.0)
   }
 }
 
 impl Renderable for JSR {
   fn render(&self) -> Vec<u8> {
-    [&[0x20], &self.0.to_le_bytes()[..]].concat()
+    [&[JSR_OPCODE], &self.0.to_le_bytes()[..]].concat()
   }
 }
 
-pub(crate) trait LDAAddressMode: AddressMode {}
+pub trait LDAAddressMode: AddressMode {}
 impl LDAAddressMode for Immediate {}
 impl LDAAddressMode for ZeroPage {}
 impl LDAAddressMode for ZeroPageX {}
@@ -886,17 +1607,30 @@ impl LDAAddressMode for AbsoluteY {}
 impl LDAAddressMode for IndexedIndirect {}
 impl LDAAddressMode for IndirectIndexed {}
 
-pub(crate) struct LDA<T: LDAAddressMode>(pub T);
-impl<T: LDAAddressMode> Instruction for LDA<T>
+pub struct LDA<T: LDAAddressMode>(pub T);
+impl<B: Bus, T: LDAAddressMode> Instruction<B> for LDA<T>
 where
-  LDA<T>: Renderable,
+  LDA<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    let value = self.0.read(memory, registers);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let page_crossed = self.0.page_crossed(bus, registers);
+    let value = self.0.read(bus, registers);
 
     registers.acc.value = value;
+    registers.flags.update_zero_negative(value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
 
-    registers.pc.value += 1 + T::LENGTH;
+    Self::CYCLES + u8::from(page_crossed)
+  }
+}
+
+impl<T: LDAAddressMode> Mnemonic for LDA<T> {
+  fn mnemonic(&self) -> &'static str {
+    "LDA"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -906,66 +1640,111 @@ impl Renderable for LDA<Immediate> {
   }
 }
 
+impl Cycles for LDA<Immediate> {
+  const CYCLES: u8 = 2;
+}
+
 impl Renderable for LDA<ZeroPage> {
   fn render(&self) -> Vec<u8> {
     [&[0xA5], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for LDA<ZeroPage> {
+  const CYCLES: u8 = 3;
+}
+
 impl Renderable for LDA<ZeroPageX> {
   fn render(&self) -> Vec<u8> {
     [&[0xB5], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for LDA<ZeroPageX> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for LDA<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0xAD], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for LDA<Absolute> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for LDA<AbsoluteX> {
   fn render(&self) -> Vec<u8> {
     [&[0xBD], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for LDA<AbsoluteX> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for LDA<AbsoluteY> {
   fn render(&self) -> Vec<u8> {
     [&[0xB9], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for LDA<AbsoluteY> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for LDA<IndexedIndirect> {
   fn render(&self) -> Vec<u8> {
     [&[0xA1], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for LDA<IndexedIndirect> {
+  const CYCLES: u8 = 6;
+}
+
 impl Renderable for LDA<IndirectIndexed> {
   fn render(&self) -> Vec<u8> {
     [&[0xB1], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) trait LDXAddressMode: AddressMode {}
+impl Cycles for LDA<IndirectIndexed> {
+  const CYCLES: u8 = 5;
+}
+
+pub trait LDXAddressMode: AddressMode {}
 impl LDXAddressMode for Immediate {}
 impl LDXAddressMode for ZeroPage {}
 impl LDXAddressMode for ZeroPageY {}
 impl LDXAddressMode for Absolute {}
 impl LDXAddressMode for AbsoluteY {}
 
-pub(crate) struct LDX<T: LDXAddressMode>(pub T);
-impl<T: LDXAddressMode> Instruction for LDX<T>
+pub struct LDX<T: LDXAddressMode>(pub T);
+impl<B: Bus, T: LDXAddressMode> Instruction<B> for LDX<T>
 where
-  LDX<T>: Renderable,
+  LDX<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    let value = self.0.read(memory, registers);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let page_crossed = self.0.page_crossed(bus, registers);
+    let value = self.0.read(bus, registers);
 
     registers.x.value = value;
+    registers.flags.update_zero_negative(value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
 
-    registers.pc.value += 1 + T::LENGTH;
+    Self::CYCLES + u8::from(page_crossed)
+  }
+}
+
+impl<T: LDXAddressMode> Mnemonic for LDX<T> {
+  fn mnemonic(&self) -> &'static str {
+    "LDX"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -975,48 +1754,81 @@ impl Renderable for LDX<Immediate> {
   }
 }
 
+impl Cycles for LDX<Immediate> {
+  const CYCLES: u8 = 2;
+}
+
 impl Renderable for LDX<ZeroPage> {
   fn render(&self) -> Vec<u8> {
     [&[0xA6], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for LDX<ZeroPage> {
+  const CYCLES: u8 = 3;
+}
+
 impl Renderable for LDX<ZeroPageY> {
   fn render(&self) -> Vec<u8> {
     [&[0xB6], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for LDX<ZeroPageY> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for LDX<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0xAE], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for LDX<Absolute> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for LDX<AbsoluteY> {
   fn render(&self) -> Vec<u8> {
     [&[0xBE], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) trait LDYAddressMode: AddressMode {}
+impl Cycles for LDX<AbsoluteY> {
+  const CYCLES: u8 = 4;
+}
+
+pub trait LDYAddressMode: AddressMode {}
 impl LDYAddressMode for Immediate {}
 impl LDYAddressMode for ZeroPage {}
 impl LDYAddressMode for ZeroPageX {}
 impl LDYAddressMode for Absolute {}
 impl LDYAddressMode for AbsoluteX {}
 
-pub(crate) struct LDY<T: LDYAddressMode>(pub T);
-impl<T: LDYAddressMode> Instruction for LDY<T>
+pub struct LDY<T: LDYAddressMode>(pub T);
+impl<B: Bus, T: LDYAddressMode> Instruction<B> for LDY<T>
 where
-  LDY<T>: Renderable,
+  LDY<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    let value = self.0.read(memory, registers);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let page_crossed = self.0.page_crossed(bus, registers);
+    let value = self.0.read(bus, registers);
 
     registers.y.value = value;
+    registers.flags.update_zero_negative(value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES + u8::from(page_crossed)
+  }
+}
 
-    registers.pc.value += 1 + T::LENGTH;
+impl<T: LDYAddressMode> Mnemonic for LDY<T> {
+  fn mnemonic(&self) -> &'static str {
+    "LDY"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -1026,48 +1838,79 @@ impl Renderable for LDY<Immediate> {
   }
 }
 
+impl Cycles for LDY<Immediate> {
+  const CYCLES: u8 = 2;
+}
+
 impl Renderable for LDY<ZeroPage> {
   fn render(&self) -> Vec<u8> {
     [&[0xA4], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for LDY<ZeroPage> {
+  const CYCLES: u8 = 3;
+}
+
 impl Renderable for LDY<ZeroPageX> {
   fn render(&self) -> Vec<u8> {
     [&[0xB4], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for LDY<ZeroPageX> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for LDY<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0xAC], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for LDY<Absolute> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for LDY<AbsoluteX> {
   fn render(&self) -> Vec<u8> {
     [&[0xBC], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) trait LSRAddressMode: AddressMode {}
+impl Cycles for LDY<AbsoluteX> {
+  const CYCLES: u8 = 4;
+}
+
+pub trait LSRAddressMode: AddressMode {}
 impl LSRAddressMode for Accumulator {}
 impl LSRAddressMode for ZeroPage {}
 impl LSRAddressMode for ZeroPageX {}
 impl LSRAddressMode for Absolute {}
 impl LSRAddressMode for AbsoluteX {}
 
-pub(crate) struct LSR<T: LSRAddressMode>(pub T);
-impl<T: LSRAddressMode> Instruction for LSR<T>
+pub struct LSR<T: LSRAddressMode>(pub T);
+impl<B: Bus, T: LSRAddressMode> Instruction<B> for LSR<T>
 where
-  LSR<T>: Renderable,
+  LSR<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    let value = self.0.read(memory, registers);
-    let shifted = value >> 1;
-    self.0.write(memory, registers, shifted);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let shifted = self.0.modify(bus, registers, shift_right);
 
-    registers.pc.value += 1 + T::LENGTH;
+    registers.flags.update_zero_negative(shifted);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
+
+impl<T: LSRAddressMode> Mnemonic for LSR<T> {
+  fn mnemonic(&self) -> &'static str {
+    "LSR"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -1077,34 +1920,62 @@ impl Renderable for LSR<Accumulator> {
   }
 }
 
+impl Cycles for LSR<Accumulator> {
+  const CYCLES: u8 = 2;
+}
+
 impl Renderable for LSR<ZeroPage> {
   fn render(&self) -> Vec<u8> {
     [&[0x46], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for LSR<ZeroPage> {
+  const CYCLES: u8 = 5;
+}
+
 impl Renderable for LSR<ZeroPageX> {
   fn render(&self) -> Vec<u8> {
     [&[0x56], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for LSR<ZeroPageX> {
+  const CYCLES: u8 = 6;
+}
+
 impl Renderable for LSR<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0x4E], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for LSR<Absolute> {
+  const CYCLES: u8 = 6;
+}
+
 impl Renderable for LSR<AbsoluteX> {
   fn render(&self) -> Vec<u8> {
     [&[0x5E], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) struct NOP;
-impl Instruction for NOP {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
-    registers.pc.value += 1;
+impl Cycles for LSR<AbsoluteX> {
+  const CYCLES: u8 = 7;
+}
+
+pub struct NOP;
+impl<B: Bus> Instruction<B> for NOP {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    2
+  }
+}
+
+impl Mnemonic for NOP {
+  fn mnemonic(&self) -> &'static str {
+    "NOP"
   }
 }
 
@@ -1114,7 +1985,7 @@ impl Renderable for NOP {
   }
 }
 
-pub(crate) trait ORAAddressMode: AddressMode {}
+pub trait ORAAddressMode: AddressMode {}
 impl ORAAddressMode for Immediate {}
 impl ORAAddressMode for ZeroPage {}
 impl ORAAddressMode for ZeroPageX {}
@@ -1124,19 +1995,30 @@ impl ORAAddressMode for AbsoluteY {}
 impl ORAAddressMode for IndexedIndirect {}
 impl ORAAddressMode for IndirectIndexed {}
 
-pub(crate) struct ORA<T: ORAAddressMode>(pub T);
-impl<T: ORAAddressMode> Instruction for ORA<T>
+pub struct ORA<T: ORAAddressMode>(pub T);
+impl<B: Bus, T: ORAAddressMode> Instruction<B> for ORA<T>
 where
-  ORA<T>: Renderable,
+  ORA<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    let value = self.0.read(memory, registers);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let page_crossed = self.0.page_crossed(bus, registers);
+    let value = self.0.read(bus, registers);
 
-    let ored = value | registers.acc.value;
+    registers.acc.value |= value;
+    registers.flags.update_zero_negative(registers.acc.value);
 
-    self.0.write(memory, registers, ored);
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
 
-    registers.pc.value += 1 + T::LENGTH;
+    Self::CYCLES + u8::from(page_crossed)
+  }
+}
+
+impl<T: ORAAddressMode> Mnemonic for ORA<T> {
+  fn mnemonic(&self) -> &'static str {
+    "ORA"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -1146,54 +2028,95 @@ impl Renderable for ORA<Immediate> {
   }
 }
 
+impl Cycles for ORA<Immediate> {
+  const CYCLES: u8 = 2;
+}
+
 impl Renderable for ORA<ZeroPage> {
   fn render(&self) -> Vec<u8> {
     [&[0x05], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ORA<ZeroPage> {
+  const CYCLES: u8 = 3;
+}
+
 impl Renderable for ORA<ZeroPageX> {
   fn render(&self) -> Vec<u8> {
     [&[0x15], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ORA<ZeroPageX> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for ORA<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0x0D], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ORA<Absolute> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for ORA<AbsoluteX> {
   fn render(&self) -> Vec<u8> {
     [&[0x1D], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ORA<AbsoluteX> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for ORA<AbsoluteY> {
   fn render(&self) -> Vec<u8> {
     [&[0x19], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ORA<AbsoluteY> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for ORA<IndexedIndirect> {
   fn render(&self) -> Vec<u8> {
     [&[0x01], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ORA<IndexedIndirect> {
+  const CYCLES: u8 = 6;
+}
+
 impl Renderable for ORA<IndirectIndexed> {
   fn render(&self) -> Vec<u8> {
     [&[0x11], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) struct TAX;
-impl Instruction for TAX {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
+impl Cycles for ORA<IndirectIndexed> {
+  const CYCLES: u8 = 5;
+}
+
+pub struct TAX;
+impl<B: Bus> Instruction<B> for TAX {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
     registers.x.value = registers.acc.value;
+    registers.flags.update_zero_negative(registers.x.value);
 
-    registers.pc.value += 1;
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    2
+  }
+}
+
+impl Mnemonic for TAX {
+  fn mnemonic(&self) -> &'static str {
+    "TAX"
   }
 }
 
@@ -1203,12 +2126,21 @@ impl Renderable for TAX {
   }
 }
 
-pub(crate) struct TXA;
-impl Instruction for TXA {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
+pub struct TXA;
+impl<B: Bus> Instruction<B> for TXA {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
     registers.acc.value = registers.x.value;
+    registers.flags.update_zero_negative(registers.acc.value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    2
+  }
+}
 
-    registers.pc.value += 1;
+impl Mnemonic for TXA {
+  fn mnemonic(&self) -> &'static str {
+    "TXA"
   }
 }
 
@@ -1218,12 +2150,21 @@ impl Renderable for TXA {
   }
 }
 
-pub(crate) struct DEX;
-impl Instruction for DEX {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
-    registers.x.value -= 1;
+pub struct DEX;
+impl<B: Bus> Instruction<B> for DEX {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.x.value = registers.x.value.wrapping_sub(1);
+    registers.flags.update_zero_negative(registers.x.value);
 
-    registers.pc.value += 1;
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    2
+  }
+}
+
+impl Mnemonic for DEX {
+  fn mnemonic(&self) -> &'static str {
+    "DEX"
   }
 }
 
@@ -1233,12 +2174,21 @@ impl Renderable for DEX {
   }
 }
 
-pub(crate) struct INX;
-impl Instruction for INX {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
-    registers.x.value += 1;
+pub struct INX;
+impl<B: Bus> Instruction<B> for INX {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.x.value = registers.x.value.wrapping_add(1);
+    registers.flags.update_zero_negative(registers.x.value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    2
+  }
+}
 
-    registers.pc.value += 1;
+impl Mnemonic for INX {
+  fn mnemonic(&self) -> &'static str {
+    "INX"
   }
 }
 
@@ -1248,12 +2198,21 @@ impl Renderable for INX {
   }
 }
 
-pub(crate) struct TAY;
-impl Instruction for TAY {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
+pub struct TAY;
+impl<B: Bus> Instruction<B> for TAY {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
     registers.y.value = registers.acc.value;
+    registers.flags.update_zero_negative(registers.y.value);
 
-    registers.pc.value += 1;
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    2
+  }
+}
+
+impl Mnemonic for TAY {
+  fn mnemonic(&self) -> &'static str {
+    "TAY"
   }
 }
 
@@ -1263,12 +2222,21 @@ impl Renderable for TAY {
   }
 }
 
-pub(crate) struct TYA;
-impl Instruction for TYA {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
+pub struct TYA;
+impl<B: Bus> Instruction<B> for TYA {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
     registers.acc.value = registers.y.value;
+    registers.flags.update_zero_negative(registers.acc.value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    2
+  }
+}
 
-    registers.pc.value += 1;
+impl Mnemonic for TYA {
+  fn mnemonic(&self) -> &'static str {
+    "TYA"
   }
 }
 
@@ -1278,12 +2246,21 @@ impl Renderable for TYA {
   }
 }
 
-pub(crate) struct DEY;
-impl Instruction for DEY {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
-    registers.y.value -= 1;
+pub struct DEY;
+impl<B: Bus> Instruction<B> for DEY {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.y.value = registers.y.value.wrapping_sub(1);
+    registers.flags.update_zero_negative(registers.y.value);
 
-    registers.pc.value += 1;
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    2
+  }
+}
+
+impl Mnemonic for DEY {
+  fn mnemonic(&self) -> &'static str {
+    "DEY"
   }
 }
 
@@ -1293,12 +2270,21 @@ impl Renderable for DEY {
   }
 }
 
-pub(crate) struct INY;
-impl Instruction for INY {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
-    registers.y.value += 1;
+pub struct INY;
+impl<B: Bus> Instruction<B> for INY {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.y.value = registers.y.value.wrapping_add(1);
+    registers.flags.update_zero_negative(registers.y.value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    2
+  }
+}
 
-    registers.pc.value += 1;
+impl Mnemonic for INY {
+  fn mnemonic(&self) -> &'static str {
+    "INY"
   }
 }
 
@@ -1308,26 +2294,35 @@ impl Renderable for INY {
   }
 }
 
-pub(crate) trait ROLAddressMode: AddressMode {}
+pub trait ROLAddressMode: AddressMode {}
 impl ROLAddressMode for Accumulator {}
 impl ROLAddressMode for ZeroPage {}
 impl ROLAddressMode for ZeroPageX {}
 impl ROLAddressMode for Absolute {}
 impl ROLAddressMode for AbsoluteX {}
 
-pub(crate) struct ROL<T: ROLAddressMode>(pub T);
-impl<T: ROLAddressMode> Instruction for ROL<T>
+pub struct ROL<T: ROLAddressMode>(pub T);
+impl<B: Bus, T: ROLAddressMode> Instruction<B> for ROL<T>
 where
-  ROL<T>: Renderable,
+  ROL<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    let value = self.0.read(memory, registers);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let rotated = self.0.modify(bus, registers, rotate_left);
 
-    let rotated = value.rotate_left(1);
+    registers.flags.update_zero_negative(rotated);
 
-    self.0.write(memory, registers, rotated);
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
 
-    registers.pc.value += 1 + T::LENGTH;
+    Self::CYCLES
+  }
+}
+
+impl<T: ROLAddressMode> Mnemonic for ROL<T> {
+  fn mnemonic(&self) -> &'static str {
+    "ROL"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -1337,50 +2332,79 @@ impl Renderable for ROL<Accumulator> {
   }
 }
 
+impl Cycles for ROL<Accumulator> {
+  const CYCLES: u8 = 2;
+}
+
 impl Renderable for ROL<ZeroPage> {
   fn render(&self) -> Vec<u8> {
     [&[0x26], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ROL<ZeroPage> {
+  const CYCLES: u8 = 5;
+}
+
 impl Renderable for ROL<ZeroPageX> {
   fn render(&self) -> Vec<u8> {
     [&[0x36], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ROL<ZeroPageX> {
+  const CYCLES: u8 = 6;
+}
+
 impl Renderable for ROL<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0x2E], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ROL<Absolute> {
+  const CYCLES: u8 = 6;
+}
+
 impl Renderable for ROL<AbsoluteX> {
   fn render(&self) -> Vec<u8> {
     [&[0x3E], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) trait RORAddressMode: AddressMode {}
+impl Cycles for ROL<AbsoluteX> {
+  const CYCLES: u8 = 7;
+}
+
+pub trait RORAddressMode: AddressMode {}
 impl RORAddressMode for Accumulator {}
 impl RORAddressMode for ZeroPage {}
 impl RORAddressMode for ZeroPageX {}
 impl RORAddressMode for Absolute {}
 impl RORAddressMode for AbsoluteX {}
 
-pub(crate) struct ROR<T: RORAddressMode>(pub T);
-impl<T: RORAddressMode> Instruction for ROR<T>
+pub struct ROR<T: RORAddressMode>(pub T);
+impl<B: Bus, T: RORAddressMode> Instruction<B> for ROR<T>
 where
-  ROR<T>: Renderable,
+  ROR<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    let value = self.0.read(memory, registers);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let rotated = self.0.modify(bus, registers, rotate_right);
 
-    let rotated = value.rotate_right(1);
+    registers.flags.update_zero_negative(rotated);
 
-    self.0.write(memory, registers, rotated);
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
 
-    registers.pc.value += 1 + T::LENGTH;
+impl<T: RORAddressMode> Mnemonic for ROR<T> {
+  fn mnemonic(&self) -> &'static str {
+    "ROR"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -1390,43 +2414,68 @@ impl Renderable for ROR<Accumulator> {
   }
 }
 
+impl Cycles for ROR<Accumulator> {
+  const CYCLES: u8 = 2;
+}
+
 impl Renderable for ROR<ZeroPage> {
   fn render(&self) -> Vec<u8> {
     [&[0x66], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ROR<ZeroPage> {
+  const CYCLES: u8 = 5;
+}
+
 impl Renderable for ROR<ZeroPageX> {
   fn render(&self) -> Vec<u8> {
     [&[0x76], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ROR<ZeroPageX> {
+  const CYCLES: u8 = 6;
+}
+
 impl Renderable for ROR<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0x6E], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for ROR<Absolute> {
+  const CYCLES: u8 = 6;
+}
+
 impl Renderable for ROR<AbsoluteX> {
   fn render(&self) -> Vec<u8> {
     [&[0x7E], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) struct RTI;
-impl Instruction for RTI {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    registers.flags.write(
-      memory.absolute(u16::from_le_bytes([0x01, registers.sp.value + 1])),
-    );
+impl Cycles for ROR<AbsoluteX> {
+  const CYCLES: u8 = 7;
+}
+
+pub struct RTI;
+impl<B: Bus> Instruction<B> for RTI {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    stack_dummy_read(bus, registers);
+    let status = pull(bus, registers);
+    registers.flags = Flags::from_byte(status);
+
+    let low = pull(bus, registers);
+    let high = pull(bus, registers);
+    registers.pc.value = u16::from_le_bytes([low, high]);
+
+    6
+  }
+}
 
-    let first =
-      memory.absolute(u16::from_le_bytes([0x01, registers.sp.value + 2]));
-    let second =
-      memory.absolute(u16::from_le_bytes([0x01, registers.sp.value + 3]));
-    registers.pc.value = u16::from_le_bytes([first, second]);
-    registers.sp.value += 3;
+impl Mnemonic for RTI {
+  fn mnemonic(&self) -> &'static str {
+    "RTI"
   }
 }
 
@@ -1436,17 +2485,24 @@ impl Renderable for RTI {
   }
 }
 
-pub(crate) struct RTS;
-impl Instruction for RTS {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    let first =
-      memory.absolute(u16::from_le_bytes([0x01, registers.sp.value + 1]));
-    let second =
-      memory.absolute(u16::from_le_bytes([0x01, registers.sp.value + 2]));
-    registers.pc.value = u16::from_le_bytes([first, second]);
-    registers.sp.value += 2;
+pub struct RTS;
+impl<B: Bus> Instruction<B> for RTS {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    stack_dummy_read(bus, registers);
+    let low = pull(bus, registers);
+    let high = pull(bus, registers);
+    registers.pc.value = u16::from_le_bytes([low, high]);
+
+    bus.dummy_read(registers.pc.value);
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    6
+  }
+}
 
-    registers.pc.value += 1;
+impl Mnemonic for RTS {
+  fn mnemonic(&self) -> &'static str {
+    "RTS"
   }
 }
 
@@ -1456,7 +2512,7 @@ impl Renderable for RTS {
   }
 }
 
-pub(crate) trait SBCAddressMode: AddressMode {}
+pub trait SBCAddressMode: AddressMode {}
 impl SBCAddressMode for Immediate {}
 impl SBCAddressMode for ZeroPage {}
 impl SBCAddressMode for ZeroPageX {}
@@ -1466,17 +2522,29 @@ impl SBCAddressMode for AbsoluteY {}
 impl SBCAddressMode for IndexedIndirect {}
 impl SBCAddressMode for IndirectIndexed {}
 
-pub(crate) struct SBC<T: SBCAddressMode>(pub T);
-impl<T: SBCAddressMode> Instruction for SBC<T>
+pub struct SBC<T: SBCAddressMode>(pub T);
+impl<B: Bus, T: SBCAddressMode> Instruction<B> for SBC<T>
 where
-  SBC<T>: Renderable,
+  SBC<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    let value = self.0.read(memory, registers);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let page_crossed = self.0.page_crossed(bus, registers);
+    let value = self.0.read(bus, registers);
+
+    subtract(registers, value);
 
-    registers.acc.value -= value;
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES + u8::from(page_crossed)
+  }
+}
 
-    registers.pc.value += 1 + T::LENGTH;
+impl<T: SBCAddressMode> Mnemonic for SBC<T> {
+  fn mnemonic(&self) -> &'static str {
+    "SBC"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -1486,49 +2554,81 @@ impl Renderable for SBC<Immediate> {
   }
 }
 
+impl Cycles for SBC<Immediate> {
+  const CYCLES: u8 = 2;
+}
+
 impl Renderable for SBC<ZeroPage> {
   fn render(&self) -> Vec<u8> {
     [&[0xE5], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for SBC<ZeroPage> {
+  const CYCLES: u8 = 3;
+}
+
 impl Renderable for SBC<ZeroPageX> {
   fn render(&self) -> Vec<u8> {
     [&[0xF5], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for SBC<ZeroPageX> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for SBC<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0xED], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for SBC<Absolute> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for SBC<AbsoluteX> {
   fn render(&self) -> Vec<u8> {
     [&[0xFD], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for SBC<AbsoluteX> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for SBC<AbsoluteY> {
   fn render(&self) -> Vec<u8> {
     [&[0xF9], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for SBC<AbsoluteY> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for SBC<IndexedIndirect> {
   fn render(&self) -> Vec<u8> {
     [&[0xE1], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for SBC<IndexedIndirect> {
+  const CYCLES: u8 = 6;
+}
+
 impl Renderable for SBC<IndirectIndexed> {
   fn render(&self) -> Vec<u8> {
     [&[0xF1], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) trait STAAddressMode: AddressMode {}
+impl Cycles for SBC<IndirectIndexed> {
+  const CYCLES: u8 = 5;
+}
+
+pub trait STAAddressMode: AddressMode {}
 impl STAAddressMode for ZeroPage {}
 impl STAAddressMode for ZeroPageX {}
 impl STAAddressMode for Absolute {}
@@ -1537,15 +2637,26 @@ impl STAAddressMode for AbsoluteY {}
 impl STAAddressMode for IndexedIndirect {}
 impl STAAddressMode for IndirectIndexed {}
 
-pub(crate) struct STA<T: STAAddressMode>(pub T);
-impl<T: STAAddressMode> Instruction for STA<T>
+pub struct STA<T: STAAddressMode>(pub T);
+impl<B: Bus, T: STAAddressMode> Instruction<B> for STA<T>
 where
-  STA<T>: Renderable,
+  STA<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    self.0.write(memory, registers, registers.acc.value);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    self.0.write(bus, registers, registers.acc.value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
 
-    registers.pc.value += 1 + T::LENGTH;
+impl<T: STAAddressMode> Mnemonic for STA<T> {
+  fn mnemonic(&self) -> &'static str {
+    "STA"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -1555,52 +2666,84 @@ impl Renderable for STA<ZeroPage> {
   }
 }
 
+impl Cycles for STA<ZeroPage> {
+  const CYCLES: u8 = 3;
+}
+
 impl Renderable for STA<ZeroPageX> {
   fn render(&self) -> Vec<u8> {
     [&[0x95], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for STA<ZeroPageX> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for STA<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0x8D], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for STA<Absolute> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for STA<AbsoluteX> {
   fn render(&self) -> Vec<u8> {
     [&[0x9D], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for STA<AbsoluteX> {
+  const CYCLES: u8 = 5;
+}
+
 impl Renderable for STA<AbsoluteY> {
   fn render(&self) -> Vec<u8> {
     [&[0x99], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for STA<AbsoluteY> {
+  const CYCLES: u8 = 5;
+}
+
 impl Renderable for STA<IndexedIndirect> {
   fn render(&self) -> Vec<u8> {
     [&[0x81], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for STA<IndexedIndirect> {
+  const CYCLES: u8 = 6;
+}
+
 impl Renderable for STA<IndirectIndexed> {
   fn render(&self) -> Vec<u8> {
     [&[0x91], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) struct TXS;
-impl Instruction for TXS {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    memory.absolute_write(
-      u16::from_le_bytes([0x01, registers.sp.value]),
-      registers.x.value as u8,
-    );
-    registers.sp.value -= 1;
+impl Cycles for STA<IndirectIndexed> {
+  const CYCLES: u8 = 6;
+}
+
+pub struct TXS;
+impl<B: Bus> Instruction<B> for TXS {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.sp.value = registers.x.value;
+
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    2
+  }
+}
 
-    registers.pc.value += 1;
+impl Mnemonic for TXS {
+  fn mnemonic(&self) -> &'static str {
+    "TXS"
   }
 }
 
@@ -1610,14 +2753,21 @@ impl Renderable for TXS {
   }
 }
 
-pub(crate) struct TSX;
-impl Instruction for TSX {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    registers.x.value =
-      memory.absolute(u16::from_le_bytes([0x01, registers.sp.value + 1])) as i8;
-    registers.sp.value += 1;
+pub struct TSX;
+impl<B: Bus> Instruction<B> for TSX {
+  fn evaluate(&self, _bus: &mut B, registers: &mut Registers) -> u8 {
+    registers.x.value = registers.sp.value;
+    registers.flags.update_zero_negative(registers.sp.value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    2
+  }
+}
 
-    registers.pc.value += 1;
+impl Mnemonic for TSX {
+  fn mnemonic(&self) -> &'static str {
+    "TSX"
   }
 }
 
@@ -1627,16 +2777,21 @@ impl Renderable for TSX {
   }
 }
 
-pub(crate) struct PHA;
-impl Instruction for PHA {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    memory.absolute_write(
-      u16::from_le_bytes([0x01, registers.sp.value]),
-      registers.acc.value as u8,
-    );
-    registers.sp.value -= 1;
+pub struct PHA;
+impl<B: Bus> Instruction<B> for PHA {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let value = registers.acc.value;
+    push(bus, registers, value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1);
 
-    registers.pc.value += 1;
+    3
+  }
+}
+
+impl Mnemonic for PHA {
+  fn mnemonic(&self) -> &'static str {
+    "PHA"
   }
 }
 
@@ -1646,14 +2801,23 @@ impl Renderable for PHA {
   }
 }
 
-pub(crate) struct PLA;
-impl Instruction for PLA {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    registers.acc.value =
-      memory.absolute(u16::from_le_bytes([0x01, registers.sp.value + 1])) as i8;
-    registers.sp.value += 1;
+pub struct PLA;
+impl<B: Bus> Instruction<B> for PLA {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    stack_dummy_read(bus, registers);
+    let value = pull(bus, registers);
+    registers.acc.value = value;
+    registers.flags.update_zero_negative(value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    4
+  }
+}
 
-    registers.pc.value += 1;
+impl Mnemonic for PLA {
+  fn mnemonic(&self) -> &'static str {
+    "PLA"
   }
 }
 
@@ -1663,18 +2827,21 @@ impl Renderable for PLA {
   }
 }
 
-pub(crate) struct PHP;
-impl Instruction for PHP {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    use crate::registers::Register;
+pub struct PHP;
+impl<B: Bus> Instruction<B> for PHP {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let status = registers.flags.to_byte(true);
+    push(bus, registers, status);
 
-    memory.absolute_write(
-      u16::from_le_bytes([0x01, registers.sp.value]),
-      registers.flags.raw() as u8,
-    );
-    registers.sp.value -= 1;
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    3
+  }
+}
 
-    registers.pc.value += 1;
+impl Mnemonic for PHP {
+  fn mnemonic(&self) -> &'static str {
+    "PHP"
   }
 }
 
@@ -1684,14 +2851,22 @@ impl Renderable for PHP {
   }
 }
 
-pub(crate) struct PLP;
-impl Instruction for PLP {
-  fn evaluate(&self, _memory: &mut Memory, registers: &mut Registers) {
-    // registers.flags.value =
-    //   memory.absolute(u16::from_le_bytes([0x01, registers.sp.value + 1])) as i8;
-    registers.sp.value += 1;
+pub struct PLP;
+impl<B: Bus> Instruction<B> for PLP {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    stack_dummy_read(bus, registers);
+    let status = pull(bus, registers);
+    registers.flags = Flags::from_byte(status);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1);
+
+    4
+  }
+}
 
-    registers.pc.value += 1;
+impl Mnemonic for PLP {
+  fn mnemonic(&self) -> &'static str {
+    "PLP"
   }
 }
 
@@ -1701,20 +2876,31 @@ impl Renderable for PLP {
   }
 }
 
-pub(crate) trait STXAddressMode: AddressMode {}
+pub trait STXAddressMode: AddressMode {}
 impl STXAddressMode for ZeroPage {}
 impl STXAddressMode for ZeroPageY {}
 impl STXAddressMode for Absolute {}
 
-pub(crate) struct STX<T: STXAddressMode>(pub T);
-impl<T: STXAddressMode> Instruction for STX<T>
+pub struct STX<T: STXAddressMode>(pub T);
+impl<B: Bus, T: STXAddressMode> Instruction<B> for STX<T>
 where
-  STX<T>: Renderable,
+  STX<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    self.0.write(memory, registers, registers.x.value);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    self.0.write(bus, registers, registers.x.value);
 
-    registers.pc.value += 1 + T::LENGTH;
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
+
+impl<T: STXAddressMode> Mnemonic for STX<T> {
+  fn mnemonic(&self) -> &'static str {
+    "STX"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -1724,32 +2910,55 @@ impl Renderable for STX<ZeroPage> {
   }
 }
 
+impl Cycles for STX<ZeroPage> {
+  const CYCLES: u8 = 3;
+}
+
 impl Renderable for STX<ZeroPageY> {
   fn render(&self) -> Vec<u8> {
     [&[0x96], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for STX<ZeroPageY> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for STX<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0x8E], self.0.render().as_slice()].concat()
   }
 }
 
-pub(crate) trait STYAddressMode: AddressMode {}
+impl Cycles for STX<Absolute> {
+  const CYCLES: u8 = 4;
+}
+
+pub trait STYAddressMode: AddressMode {}
 impl STYAddressMode for ZeroPage {}
 impl STYAddressMode for ZeroPageX {}
 impl STYAddressMode for Absolute {}
 
-pub(crate) struct STY<T: STYAddressMode>(pub T);
-impl<T: STYAddressMode> Instruction for STY<T>
+pub struct STY<T: STYAddressMode>(pub T);
+impl<B: Bus, T: STYAddressMode> Instruction<B> for STY<T>
 where
-  STY<T>: Renderable,
+  STY<T>: Renderable + Cycles,
 {
-  fn evaluate(&self, memory: &mut Memory, registers: &mut Registers) {
-    self.0.write(memory, registers, registers.y.value);
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    self.0.write(bus, registers, registers.y.value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
 
-    registers.pc.value += 1 + T::LENGTH;
+    Self::CYCLES
+  }
+}
+
+impl<T: STYAddressMode> Mnemonic for STY<T> {
+  fn mnemonic(&self) -> &'static str {
+    "STY"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
   }
 }
 
@@ -1759,14 +2968,175 @@ impl Renderable for STY<ZeroPage> {
   }
 }
 
+impl Cycles for STY<ZeroPage> {
+  const CYCLES: u8 = 3;
+}
+
 impl Renderable for STY<ZeroPageX> {
   fn render(&self) -> Vec<u8> {
     [&[0x94], self.0.render().as_slice()].concat()
   }
 }
 
+impl Cycles for STY<ZeroPageX> {
+  const CYCLES: u8 = 4;
+}
+
 impl Renderable for STY<Absolute> {
   fn render(&self) -> Vec<u8> {
     [&[0x8C], self.0.render().as_slice()].concat()
   }
 }
+
+impl Cycles for STY<Absolute> {
+  const CYCLES: u8 = 4;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::interp::{interp, Variant};
+  use super::*;
+
+  // A and the N, V, Z and C flags after running `instruction` in decimal
+  // mode from `acc` and `carry`.
+  fn decimal<I: Instruction>(
+    instruction: I,
+    acc: u8,
+    carry: bool,
+  ) -> (u8, [bool; 4]) {
+    let mut memory = Memory::new();
+    let mut registers = Registers::default();
+    registers.acc.value = acc;
+    registers.flags.carry = carry;
+    registers.flags.decimal_mode = true;
+
+    instruction.evaluate(&mut memory, &mut registers);
+
+    let flags = &registers.flags;
+    assert!(flags.decimal_mode);
+    (
+      registers.acc.value,
+      [flags.negative, flags.overflow, flags.zero, flags.carry],
+    )
+  }
+
+  #[test]
+  fn decimal_add_carries_between_digits() {
+    // N V Z C
+    assert_eq!(
+      decimal(ADC(Immediate(0x34)), 0x12, false),
+      (0x46, [false, false, false, false])
+    );
+    assert_eq!(
+      decimal(ADC(Immediate(0x46)), 0x58, true),
+      (0x05, [true, true, false, true])
+    );
+  }
+
+  #[test]
+  fn decimal_add_takes_z_from_the_binary_sum() {
+    // $99 + $01 is $00 in BCD, but $9A in binary, so Z stays clear while N
+    // comes from the high digit before it is adjusted.
+    assert_eq!(
+      decimal(ADC(Immediate(0x01)), 0x99, false),
+      (0x00, [true, false, false, true])
+    );
+    // And V is set as if the digits were added in binary.
+    assert_eq!(
+      decimal(ADC(Immediate(0x00)), 0x79, true),
+      (0x80, [true, true, false, false])
+    );
+  }
+
+  #[test]
+  fn decimal_add_adjusts_invalid_digits() {
+    assert_eq!(
+      decimal(ADC(Immediate(0x00)), 0x1A, false),
+      (0x20, [false, false, false, false])
+    );
+    assert_eq!(
+      decimal(ADC(Immediate(0x00)), 0xA0, false),
+      (0x00, [true, false, false, true])
+    );
+  }
+
+  #[test]
+  fn decimal_subtract_borrows_between_digits() {
+    assert_eq!(
+      decimal(SBC(Immediate(0x12)), 0x46, true),
+      (0x34, [false, false, false, true])
+    );
+    assert_eq!(
+      decimal(SBC(Immediate(0x13)), 0x40, true),
+      (0x27, [false, false, false, true])
+    );
+    // Carry clear borrows one more.
+    assert_eq!(
+      decimal(SBC(Immediate(0x25)), 0x50, false),
+      (0x24, [false, false, false, true])
+    );
+  }
+
+  #[test]
+  fn decimal_subtract_takes_flags_from_the_binary_difference() {
+    assert_eq!(
+      decimal(SBC(Immediate(0x01)), 0x00, true),
+      (0x99, [true, false, false, false])
+    );
+    assert_eq!(
+      decimal(SBC(Immediate(0x00)), 0x00, true),
+      (0x00, [false, false, true, true])
+    );
+  }
+
+  #[test]
+  fn binary_ignores_decimal_mode() {
+    assert_eq!(
+      decimal(Binary(ADC(Immediate(0x01))), 0x09, false),
+      (0x0A, [false, false, false, false])
+    );
+    assert_eq!(
+      decimal(Binary(SBC(Immediate(0x01))), 0x10, true),
+      (0x0F, [false, false, false, true])
+    );
+  }
+
+  #[test]
+  fn ricoh_2a03_decodes_arithmetic_as_binary() {
+    // ADC #$01
+    let mut memory = Memory::new();
+    memory.write(0x0000, 0x69);
+    memory.write(0x0001, 0x01);
+    let instruction =
+      interp(&memory, 0x0000, Variant::Ricoh2A03, false).unwrap();
+
+    let mut registers = Registers::default();
+    registers.acc.value = 0x09;
+    registers.flags.decimal_mode = true;
+    instruction.evaluate(&mut memory, &mut registers);
+
+    assert_eq!(registers.acc.value, 0x0A);
+    assert!(registers.flags.decimal_mode);
+  }
+
+  #[test]
+  fn ricoh_2a03_decodes_arr_as_binary() {
+    // ARR #$60, which an NMOS part in decimal mode would adjust to $90 with
+    // the carry set.
+    let mut memory = Memory::new();
+    memory.write(0x0000, 0x6B);
+    memory.write(0x0001, 0x60);
+    let instruction =
+      interp(&memory, 0x0000, Variant::Ricoh2A03, true).unwrap();
+
+    let mut registers = Registers::default();
+    registers.acc.value = 0x60;
+    registers.flags.decimal_mode = true;
+    instruction.evaluate(&mut memory, &mut registers);
+
+    assert_eq!(registers.acc.value, 0x30);
+    assert!(!registers.flags.carry);
+    assert!(registers.flags.overflow);
+    assert!(registers.flags.decimal_mode);
+  }
+}
@@ -0,0 +1,1206 @@
+use super::addressing::*;
+use super::interp::{arithmetic, DecodedInstruction, Variant};
+use super::*;
+
+// Stable undocumented NMOS opcodes. Most combine two documented operations,
+// so they are built from the same helpers.
+
+// Undocumented encodings that behave exactly like another instruction,
+// rendered with their own opcode.
+pub struct Alias<I>(pub u8, pub I);
+impl<B: Bus, I: Instruction<B>> Instruction<B> for Alias<I> {
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    self.1.evaluate(bus, registers)
+  }
+}
+
+impl<I: Mnemonic> Mnemonic for Alias<I> {
+  fn mnemonic(&self) -> &'static str {
+    self.1.mnemonic()
+  }
+  fn operand(&self, pc: u16) -> String {
+    self.1.operand(pc)
+  }
+}
+
+impl<I: Renderable> Renderable for Alias<I> {
+  fn render(&self) -> Vec<u8> {
+    let mut bytes = self.1.render();
+    bytes[0] = self.0;
+    bytes
+  }
+}
+
+// LDA and LDX at once.
+pub trait LAXAddressMode: AddressMode {}
+impl LAXAddressMode for ZeroPage {}
+impl LAXAddressMode for ZeroPageY {}
+impl LAXAddressMode for Absolute {}
+impl LAXAddressMode for AbsoluteY {}
+impl LAXAddressMode for IndexedIndirect {}
+impl LAXAddressMode for IndirectIndexed {}
+
+pub struct LAX<T: LAXAddressMode>(pub T);
+impl<B: Bus, T: LAXAddressMode> Instruction<B> for LAX<T>
+where
+  LAX<T>: Renderable + Cycles,
+{
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let page_crossed = self.0.page_crossed(bus, registers);
+    let value = self.0.read(bus, registers);
+
+    registers.acc.value = value;
+    registers.x.value = value;
+    registers.flags.update_zero_negative(value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES + u8::from(page_crossed)
+  }
+}
+
+impl<T: LAXAddressMode> Mnemonic for LAX<T> {
+  fn mnemonic(&self) -> &'static str {
+    "LAX"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
+  }
+}
+
+impl Renderable for LAX<ZeroPage> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xA7], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for LAX<ZeroPage> {
+  const CYCLES: u8 = 3;
+}
+
+impl Renderable for LAX<ZeroPageY> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xB7], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for LAX<ZeroPageY> {
+  const CYCLES: u8 = 4;
+}
+
+impl Renderable for LAX<Absolute> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xAF], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for LAX<Absolute> {
+  const CYCLES: u8 = 4;
+}
+
+impl Renderable for LAX<AbsoluteY> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xBF], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for LAX<AbsoluteY> {
+  const CYCLES: u8 = 4;
+}
+
+impl Renderable for LAX<IndexedIndirect> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xA3], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for LAX<IndexedIndirect> {
+  const CYCLES: u8 = 6;
+}
+
+impl Renderable for LAX<IndirectIndexed> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xB3], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for LAX<IndirectIndexed> {
+  const CYCLES: u8 = 5;
+}
+
+// Store A AND X, leaving flags alone.
+pub trait SAXAddressMode: AddressMode {}
+impl SAXAddressMode for ZeroPage {}
+impl SAXAddressMode for ZeroPageY {}
+impl SAXAddressMode for Absolute {}
+impl SAXAddressMode for IndexedIndirect {}
+
+pub struct SAX<T: SAXAddressMode>(pub T);
+impl<B: Bus, T: SAXAddressMode> Instruction<B> for SAX<T>
+where
+  SAX<T>: Renderable + Cycles,
+{
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let value = registers.acc.value & registers.x.value;
+    self.0.write(bus, registers, value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
+
+impl<T: SAXAddressMode> Mnemonic for SAX<T> {
+  fn mnemonic(&self) -> &'static str {
+    "SAX"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
+  }
+}
+
+impl Renderable for SAX<ZeroPage> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x87], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for SAX<ZeroPage> {
+  const CYCLES: u8 = 3;
+}
+
+impl Renderable for SAX<ZeroPageY> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x97], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for SAX<ZeroPageY> {
+  const CYCLES: u8 = 4;
+}
+
+impl Renderable for SAX<Absolute> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x8F], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for SAX<Absolute> {
+  const CYCLES: u8 = 4;
+}
+
+impl Renderable for SAX<IndexedIndirect> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x83], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for SAX<IndexedIndirect> {
+  const CYCLES: u8 = 6;
+}
+
+// ASL then ORA.
+pub trait SLOAddressMode: AddressMode {}
+impl SLOAddressMode for ZeroPage {}
+impl SLOAddressMode for ZeroPageX {}
+impl SLOAddressMode for Absolute {}
+impl SLOAddressMode for AbsoluteX {}
+impl SLOAddressMode for AbsoluteY {}
+impl SLOAddressMode for IndexedIndirect {}
+impl SLOAddressMode for IndirectIndexed {}
+
+pub struct SLO<T: SLOAddressMode>(pub T);
+impl<B: Bus, T: SLOAddressMode> Instruction<B> for SLO<T>
+where
+  SLO<T>: Renderable + Cycles,
+{
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let shifted = self.0.modify(bus, registers, shift_left);
+
+    registers.acc.value |= shifted;
+    registers.flags.update_zero_negative(registers.acc.value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
+
+impl<T: SLOAddressMode> Mnemonic for SLO<T> {
+  fn mnemonic(&self) -> &'static str {
+    "SLO"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
+  }
+}
+
+impl Renderable for SLO<ZeroPage> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x07], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for SLO<ZeroPage> {
+  const CYCLES: u8 = 5;
+}
+
+impl Renderable for SLO<ZeroPageX> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x17], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for SLO<ZeroPageX> {
+  const CYCLES: u8 = 6;
+}
+
+impl Renderable for SLO<Absolute> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x0F], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for SLO<Absolute> {
+  const CYCLES: u8 = 6;
+}
+
+impl Renderable for SLO<AbsoluteX> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x1F], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for SLO<AbsoluteX> {
+  const CYCLES: u8 = 7;
+}
+
+impl Renderable for SLO<AbsoluteY> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x1B], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for SLO<AbsoluteY> {
+  const CYCLES: u8 = 7;
+}
+
+impl Renderable for SLO<IndexedIndirect> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x03], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for SLO<IndexedIndirect> {
+  const CYCLES: u8 = 8;
+}
+
+impl Renderable for SLO<IndirectIndexed> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x13], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for SLO<IndirectIndexed> {
+  const CYCLES: u8 = 8;
+}
+
+// ROL then AND.
+pub trait RLAAddressMode: AddressMode {}
+impl RLAAddressMode for ZeroPage {}
+impl RLAAddressMode for ZeroPageX {}
+impl RLAAddressMode for Absolute {}
+impl RLAAddressMode for AbsoluteX {}
+impl RLAAddressMode for AbsoluteY {}
+impl RLAAddressMode for IndexedIndirect {}
+impl RLAAddressMode for IndirectIndexed {}
+
+pub struct RLA<T: RLAAddressMode>(pub T);
+impl<B: Bus, T: RLAAddressMode> Instruction<B> for RLA<T>
+where
+  RLA<T>: Renderable + Cycles,
+{
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let rotated = self.0.modify(bus, registers, rotate_left);
+
+    registers.acc.value &= rotated;
+    registers.flags.update_zero_negative(registers.acc.value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
+
+impl<T: RLAAddressMode> Mnemonic for RLA<T> {
+  fn mnemonic(&self) -> &'static str {
+    "RLA"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
+  }
+}
+
+impl Renderable for RLA<ZeroPage> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x27], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for RLA<ZeroPage> {
+  const CYCLES: u8 = 5;
+}
+
+impl Renderable for RLA<ZeroPageX> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x37], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for RLA<ZeroPageX> {
+  const CYCLES: u8 = 6;
+}
+
+impl Renderable for RLA<Absolute> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x2F], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for RLA<Absolute> {
+  const CYCLES: u8 = 6;
+}
+
+impl Renderable for RLA<AbsoluteX> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x3F], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for RLA<AbsoluteX> {
+  const CYCLES: u8 = 7;
+}
+
+impl Renderable for RLA<AbsoluteY> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x3B], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for RLA<AbsoluteY> {
+  const CYCLES: u8 = 7;
+}
+
+impl Renderable for RLA<IndexedIndirect> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x23], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for RLA<IndexedIndirect> {
+  const CYCLES: u8 = 8;
+}
+
+impl Renderable for RLA<IndirectIndexed> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x33], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for RLA<IndirectIndexed> {
+  const CYCLES: u8 = 8;
+}
+
+// LSR then EOR.
+pub trait SREAddressMode: AddressMode {}
+impl SREAddressMode for ZeroPage {}
+impl SREAddressMode for ZeroPageX {}
+impl SREAddressMode for Absolute {}
+impl SREAddressMode for AbsoluteX {}
+impl SREAddressMode for AbsoluteY {}
+impl SREAddressMode for IndexedIndirect {}
+impl SREAddressMode for IndirectIndexed {}
+
+pub struct SRE<T: SREAddressMode>(pub T);
+impl<B: Bus, T: SREAddressMode> Instruction<B> for SRE<T>
+where
+  SRE<T>: Renderable + Cycles,
+{
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let shifted = self.0.modify(bus, registers, shift_right);
+
+    registers.acc.value ^= shifted;
+    registers.flags.update_zero_negative(registers.acc.value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
+
+impl<T: SREAddressMode> Mnemonic for SRE<T> {
+  fn mnemonic(&self) -> &'static str {
+    "SRE"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
+  }
+}
+
+impl Renderable for SRE<ZeroPage> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x47], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for SRE<ZeroPage> {
+  const CYCLES: u8 = 5;
+}
+
+impl Renderable for SRE<ZeroPageX> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x57], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for SRE<ZeroPageX> {
+  const CYCLES: u8 = 6;
+}
+
+impl Renderable for SRE<Absolute> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x4F], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for SRE<Absolute> {
+  const CYCLES: u8 = 6;
+}
+
+impl Renderable for SRE<AbsoluteX> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x5F], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for SRE<AbsoluteX> {
+  const CYCLES: u8 = 7;
+}
+
+impl Renderable for SRE<AbsoluteY> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x5B], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for SRE<AbsoluteY> {
+  const CYCLES: u8 = 7;
+}
+
+impl Renderable for SRE<IndexedIndirect> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x43], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for SRE<IndexedIndirect> {
+  const CYCLES: u8 = 8;
+}
+
+impl Renderable for SRE<IndirectIndexed> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x53], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for SRE<IndirectIndexed> {
+  const CYCLES: u8 = 8;
+}
+
+// ROR then ADC, adding in the carry rotated out.
+pub trait RRAAddressMode: AddressMode {}
+impl RRAAddressMode for ZeroPage {}
+impl RRAAddressMode for ZeroPageX {}
+impl RRAAddressMode for Absolute {}
+impl RRAAddressMode for AbsoluteX {}
+impl RRAAddressMode for AbsoluteY {}
+impl RRAAddressMode for IndexedIndirect {}
+impl RRAAddressMode for IndirectIndexed {}
+
+pub struct RRA<T: RRAAddressMode>(pub T);
+impl<B: Bus, T: RRAAddressMode> Instruction<B> for RRA<T>
+where
+  RRA<T>: Renderable + Cycles,
+{
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let rotated = self.0.modify(bus, registers, rotate_right);
+
+    add(registers, rotated);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
+
+impl<T: RRAAddressMode> Mnemonic for RRA<T> {
+  fn mnemonic(&self) -> &'static str {
+    "RRA"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
+  }
+}
+
+impl Renderable for RRA<ZeroPage> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x67], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for RRA<ZeroPage> {
+  const CYCLES: u8 = 5;
+}
+
+impl Renderable for RRA<ZeroPageX> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x77], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for RRA<ZeroPageX> {
+  const CYCLES: u8 = 6;
+}
+
+impl Renderable for RRA<Absolute> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x6F], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for RRA<Absolute> {
+  const CYCLES: u8 = 6;
+}
+
+impl Renderable for RRA<AbsoluteX> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x7F], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for RRA<AbsoluteX> {
+  const CYCLES: u8 = 7;
+}
+
+impl Renderable for RRA<AbsoluteY> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x7B], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for RRA<AbsoluteY> {
+  const CYCLES: u8 = 7;
+}
+
+impl Renderable for RRA<IndexedIndirect> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x63], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for RRA<IndexedIndirect> {
+  const CYCLES: u8 = 8;
+}
+
+impl Renderable for RRA<IndirectIndexed> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x73], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for RRA<IndirectIndexed> {
+  const CYCLES: u8 = 8;
+}
+
+// DEC then CMP.
+pub trait DCPAddressMode: AddressMode {}
+impl DCPAddressMode for ZeroPage {}
+impl DCPAddressMode for ZeroPageX {}
+impl DCPAddressMode for Absolute {}
+impl DCPAddressMode for AbsoluteX {}
+impl DCPAddressMode for AbsoluteY {}
+impl DCPAddressMode for IndexedIndirect {}
+impl DCPAddressMode for IndirectIndexed {}
+
+pub struct DCP<T: DCPAddressMode>(pub T);
+impl<B: Bus, T: DCPAddressMode> Instruction<B> for DCP<T>
+where
+  DCP<T>: Renderable + Cycles,
+{
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let value = self
+      .0
+      .modify(bus, registers, |_, value| value.wrapping_sub(1));
+
+    compare(registers, registers.acc.value, value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
+
+impl<T: DCPAddressMode> Mnemonic for DCP<T> {
+  fn mnemonic(&self) -> &'static str {
+    "DCP"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
+  }
+}
+
+impl Renderable for DCP<ZeroPage> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xC7], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for DCP<ZeroPage> {
+  const CYCLES: u8 = 5;
+}
+
+impl Renderable for DCP<ZeroPageX> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xD7], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for DCP<ZeroPageX> {
+  const CYCLES: u8 = 6;
+}
+
+impl Renderable for DCP<Absolute> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xCF], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for DCP<Absolute> {
+  const CYCLES: u8 = 6;
+}
+
+impl Renderable for DCP<AbsoluteX> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xDF], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for DCP<AbsoluteX> {
+  const CYCLES: u8 = 7;
+}
+
+impl Renderable for DCP<AbsoluteY> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xDB], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for DCP<AbsoluteY> {
+  const CYCLES: u8 = 7;
+}
+
+impl Renderable for DCP<IndexedIndirect> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xC3], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for DCP<IndexedIndirect> {
+  const CYCLES: u8 = 8;
+}
+
+impl Renderable for DCP<IndirectIndexed> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xD3], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for DCP<IndirectIndexed> {
+  const CYCLES: u8 = 8;
+}
+
+// INC then SBC.
+pub trait ISCAddressMode: AddressMode {}
+impl ISCAddressMode for ZeroPage {}
+impl ISCAddressMode for ZeroPageX {}
+impl ISCAddressMode for Absolute {}
+impl ISCAddressMode for AbsoluteX {}
+impl ISCAddressMode for AbsoluteY {}
+impl ISCAddressMode for IndexedIndirect {}
+impl ISCAddressMode for IndirectIndexed {}
+
+pub struct ISC<T: ISCAddressMode>(pub T);
+impl<B: Bus, T: ISCAddressMode> Instruction<B> for ISC<T>
+where
+  ISC<T>: Renderable + Cycles,
+{
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let value = self
+      .0
+      .modify(bus, registers, |_, value| value.wrapping_add(1));
+
+    subtract(registers, value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
+
+impl<T: ISCAddressMode> Mnemonic for ISC<T> {
+  fn mnemonic(&self) -> &'static str {
+    "ISC"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
+  }
+}
+
+impl Renderable for ISC<ZeroPage> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xE7], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for ISC<ZeroPage> {
+  const CYCLES: u8 = 5;
+}
+
+impl Renderable for ISC<ZeroPageX> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xF7], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for ISC<ZeroPageX> {
+  const CYCLES: u8 = 6;
+}
+
+impl Renderable for ISC<Absolute> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xEF], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for ISC<Absolute> {
+  const CYCLES: u8 = 6;
+}
+
+impl Renderable for ISC<AbsoluteX> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xFF], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for ISC<AbsoluteX> {
+  const CYCLES: u8 = 7;
+}
+
+impl Renderable for ISC<AbsoluteY> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xFB], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for ISC<AbsoluteY> {
+  const CYCLES: u8 = 7;
+}
+
+impl Renderable for ISC<IndexedIndirect> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xE3], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for ISC<IndexedIndirect> {
+  const CYCLES: u8 = 8;
+}
+
+impl Renderable for ISC<IndirectIndexed> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xF3], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for ISC<IndirectIndexed> {
+  const CYCLES: u8 = 8;
+}
+
+// AND, then copy N into C.
+pub trait ANCAddressMode: AddressMode {}
+impl ANCAddressMode for Immediate {}
+
+pub struct ANC<T: ANCAddressMode>(pub T);
+impl<B: Bus, T: ANCAddressMode> Instruction<B> for ANC<T>
+where
+  ANC<T>: Renderable + Cycles,
+{
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let value = self.0.read(bus, registers);
+
+    registers.acc.value &= value;
+    registers.flags.update_zero_negative(registers.acc.value);
+    registers.flags.carry = registers.flags.negative;
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
+
+impl<T: ANCAddressMode> Mnemonic for ANC<T> {
+  fn mnemonic(&self) -> &'static str {
+    "ANC"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
+  }
+}
+
+impl Renderable for ANC<Immediate> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x0B], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for ANC<Immediate> {
+  const CYCLES: u8 = 2;
+}
+
+// AND then LSR A.
+pub trait ALRAddressMode: AddressMode {}
+impl ALRAddressMode for Immediate {}
+
+pub struct ALR<T: ALRAddressMode>(pub T);
+impl<B: Bus, T: ALRAddressMode> Instruction<B> for ALR<T>
+where
+  ALR<T>: Renderable + Cycles,
+{
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let value = self.0.read(bus, registers);
+
+    let masked = registers.acc.value & value;
+    registers.acc.value = shift_right(registers, masked);
+    registers.flags.update_zero_negative(registers.acc.value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
+
+impl<T: ALRAddressMode> Mnemonic for ALR<T> {
+  fn mnemonic(&self) -> &'static str {
+    "ALR"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
+  }
+}
+
+impl Renderable for ALR<Immediate> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x4B], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for ALR<Immediate> {
+  const CYCLES: u8 = 2;
+}
+
+// AND then ROR A, with C and V taken from bits 6 and 5 of the result.
+// In decimal mode NMOS parts also adjust each nibble.
+pub trait ARRAddressMode: AddressMode {}
+impl ARRAddressMode for Immediate {}
+
+pub struct ARR<T: ARRAddressMode>(pub T);
+impl<B: Bus, T: ARRAddressMode> Instruction<B> for ARR<T>
+where
+  ARR<T>: Renderable + Cycles,
+{
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let value = self.0.read(bus, registers);
+
+    let masked = registers.acc.value & value;
+    let mut result = (masked >> 1) | (u8::from(registers.flags.carry) << 7);
+    registers.flags.update_zero_negative(result);
+
+    if registers.flags.decimal_mode {
+      registers.flags.overflow = ((masked ^ result) & 0x40) != 0;
+      if (masked & 0x0F) + (masked & 0x01) > 0x05 {
+        result = (result & 0xF0) | (result.wrapping_add(0x06) & 0x0F);
+      }
+      let high = u16::from(masked & 0xF0) + u16::from(masked & 0x10);
+      registers.flags.carry = high > 0x50;
+      if registers.flags.carry {
+        result = result.wrapping_add(0x60);
+      }
+    } else {
+      registers.flags.carry = (result & 0x40) != 0;
+      registers.flags.overflow = ((result >> 6) ^ (result >> 5)) & 0x01 != 0;
+    }
+
+    registers.acc.value = result;
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
+
+impl<T: ARRAddressMode> Mnemonic for ARR<T> {
+  fn mnemonic(&self) -> &'static str {
+    "ARR"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
+  }
+}
+
+impl Renderable for ARR<Immediate> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x6B], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for ARR<Immediate> {
+  const CYCLES: u8 = 2;
+}
+
+// X becomes A AND X minus the operand, compared without borrow.
+pub trait SBXAddressMode: AddressMode {}
+impl SBXAddressMode for Immediate {}
+
+pub struct SBX<T: SBXAddressMode>(pub T);
+impl<B: Bus, T: SBXAddressMode> Instruction<B> for SBX<T>
+where
+  SBX<T>: Renderable + Cycles,
+{
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let value = self.0.read(bus, registers);
+
+    let masked = registers.acc.value & registers.x.value;
+    compare(registers, masked, value);
+    registers.x.value = masked.wrapping_sub(value);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES
+  }
+}
+
+impl<T: SBXAddressMode> Mnemonic for SBX<T> {
+  fn mnemonic(&self) -> &'static str {
+    "SBX"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
+  }
+}
+
+impl Renderable for SBX<Immediate> {
+  fn render(&self) -> Vec<u8> {
+    [&[0xCB], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for SBX<Immediate> {
+  const CYCLES: u8 = 2;
+}
+
+// Reads its operand and discards it.
+pub trait IGNAddressMode: AddressMode {}
+impl IGNAddressMode for Immediate {}
+impl IGNAddressMode for ZeroPage {}
+impl IGNAddressMode for ZeroPageX {}
+impl IGNAddressMode for Absolute {}
+impl IGNAddressMode for AbsoluteX {}
+
+pub struct IGN<T: IGNAddressMode>(pub T);
+impl<B: Bus, T: IGNAddressMode> Instruction<B> for IGN<T>
+where
+  IGN<T>: Renderable + Cycles,
+{
+  fn evaluate(&self, bus: &mut B, registers: &mut Registers) -> u8 {
+    let page_crossed = self.0.page_crossed(bus, registers);
+    self.0.read(bus, registers);
+
+    registers.pc.value = registers.pc.value.wrapping_add(1 + T::LENGTH);
+
+    Self::CYCLES + u8::from(page_crossed)
+  }
+}
+
+impl<T: IGNAddressMode> Mnemonic for IGN<T> {
+  fn mnemonic(&self) -> &'static str {
+    "NOP"
+  }
+  fn operand(&self, _pc: u16) -> String {
+    self.0.syntax()
+  }
+}
+
+impl Renderable for IGN<Immediate> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x80], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for IGN<Immediate> {
+  const CYCLES: u8 = 2;
+}
+
+impl Renderable for IGN<ZeroPage> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x04], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for IGN<ZeroPage> {
+  const CYCLES: u8 = 3;
+}
+
+impl Renderable for IGN<ZeroPageX> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x14], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for IGN<ZeroPageX> {
+  const CYCLES: u8 = 4;
+}
+
+impl Renderable for IGN<Absolute> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x0C], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for IGN<Absolute> {
+  const CYCLES: u8 = 4;
+}
+
+impl Renderable for IGN<AbsoluteX> {
+  fn render(&self) -> Vec<u8> {
+    [&[0x1C], self.0.render().as_slice()].concat()
+  }
+}
+
+impl Cycles for IGN<AbsoluteX> {
+  const CYCLES: u8 = 4;
+}
+
+// Decode the stable undocumented opcodes, or None for the ones that jam the
+// processor or behave unpredictably.
+pub(crate) fn interp<B: Bus>(
+  bus: &B,
+  pc: u16,
+  variant: Variant,
+) -> Option<DecodedInstruction<B>> {
+  let op = bus.peek(pc);
+  let byte = || bus.peek(pc.wrapping_add(1));
+  let word = || {
+    u16::from_le_bytes([
+      bus.peek(pc.wrapping_add(1)),
+      bus.peek(pc.wrapping_add(2)),
+    ])
+  };
+
+  let instruction: DecodedInstruction<B> = match op {
+    0xA7 => Box::new(LAX(ZeroPage(byte()))),
+    0xB7 => Box::new(LAX(ZeroPageY(byte()))),
+    0xAF => Box::new(LAX(Absolute(word()))),
+    0xBF => Box::new(LAX(AbsoluteY(word()))),
+    0xA3 => Box::new(LAX(IndexedIndirect(byte()))),
+    0xB3 => Box::new(LAX(IndirectIndexed(byte()))),
+
+    0x87 => Box::new(SAX(ZeroPage(byte()))),
+    0x97 => Box::new(SAX(ZeroPageY(byte()))),
+    0x8F => Box::new(SAX(Absolute(word()))),
+    0x83 => Box::new(SAX(IndexedIndirect(byte()))),
+
+    0x07 => Box::new(SLO(ZeroPage(byte()))),
+    0x17 => Box::new(SLO(ZeroPageX(byte()))),
+    0x0F => Box::new(SLO(Absolute(word()))),
+    0x1F => Box::new(SLO(AbsoluteX(word()))),
+    0x1B => Box::new(SLO(AbsoluteY(word()))),
+    0x03 => Box::new(SLO(IndexedIndirect(byte()))),
+    0x13 => Box::new(SLO(IndirectIndexed(byte()))),
+
+    0x27 => Box::new(RLA(ZeroPage(byte()))),
+    0x37 => Box::new(RLA(ZeroPageX(byte()))),
+    0x2F => Box::new(RLA(Absolute(word()))),
+    0x3F => Box::new(RLA(AbsoluteX(word()))),
+    0x3B => Box::new(RLA(AbsoluteY(word()))),
+    0x23 => Box::new(RLA(IndexedIndirect(byte()))),
+    0x33 => Box::new(RLA(IndirectIndexed(byte()))),
+
+    0x47 => Box::new(SRE(ZeroPage(byte()))),
+    0x57 => Box::new(SRE(ZeroPageX(byte()))),
+    0x4F => Box::new(SRE(Absolute(word()))),
+    0x5F => Box::new(SRE(AbsoluteX(word()))),
+    0x5B => Box::new(SRE(AbsoluteY(word()))),
+    0x43 => Box::new(SRE(IndexedIndirect(byte()))),
+    0x53 => Box::new(SRE(IndirectIndexed(byte()))),
+
+    0x67 => arithmetic(RRA(ZeroPage(byte())), variant),
+    0x77 => arithmetic(RRA(ZeroPageX(byte())), variant),
+    0x6F => arithmetic(RRA(Absolute(word())), variant),
+    0x7F => arithmetic(RRA(AbsoluteX(word())), variant),
+    0x7B => arithmetic(RRA(AbsoluteY(word())), variant),
+    0x63 => arithmetic(RRA(IndexedIndirect(byte())), variant),
+    0x73 => arithmetic(RRA(IndirectIndexed(byte())), variant),
+
+    0xC7 => Box::new(DCP(ZeroPage(byte()))),
+    0xD7 => Box::new(DCP(ZeroPageX(byte()))),
+    0xCF => Box::new(DCP(Absolute(word()))),
+    0xDF => Box::new(DCP(AbsoluteX(word()))),
+    0xDB => Box::new(DCP(AbsoluteY(word()))),
+    0xC3 => Box::new(DCP(IndexedIndirect(byte()))),
+    0xD3 => Box::new(DCP(IndirectIndexed(byte()))),
+
+    0xE7 => arithmetic(ISC(ZeroPage(byte())), variant),
+    0xF7 => arithmetic(ISC(ZeroPageX(byte())), variant),
+    0xEF => arithmetic(ISC(Absolute(word())), variant),
+    0xFF => arithmetic(ISC(AbsoluteX(word())), variant),
+    0xFB => arithmetic(ISC(AbsoluteY(word())), variant),
+    0xE3 => arithmetic(ISC(IndexedIndirect(byte())), variant),
+    0xF3 => arithmetic(ISC(IndirectIndexed(byte())), variant),
+
+    0x0B => Box::new(ANC(Immediate(byte()))),
+    0x2B => Box::new(Alias(0x2B, ANC(Immediate(byte())))),
+    0x4B => Box::new(ALR(Immediate(byte()))),
+    0x6B => arithmetic(ARR(Immediate(byte())), variant),
+    0xCB => Box::new(SBX(Immediate(byte()))),
+    0xEB => arithmetic(Alias(0xEB, SBC(Immediate(byte()))), variant),
+
+    0x1A | 0x3A | 0x5A | 0x7A | 0xDA | 0xFA => Box::new(Alias(op, NOP)),
+
+    0x80 => Box::new(IGN(Immediate(byte()))),
+    0x82 | 0x89 | 0xC2 | 0xE2 => Box::new(Alias(op, IGN(Immediate(byte())))),
+    0x04 => Box::new(IGN(ZeroPage(byte()))),
+    0x44 | 0x64 => Box::new(Alias(op, IGN(ZeroPage(byte())))),
+    0x14 => Box::new(IGN(ZeroPageX(byte()))),
+    0x34 | 0x54 | 0x74 | 0xD4 | 0xF4 => {
+      Box::new(Alias(op, IGN(ZeroPageX(byte()))))
+    }
+    0x0C => Box::new(IGN(Absolute(word()))),
+    0x1C => Box::new(IGN(AbsoluteX(word()))),
+    0x3C | 0x5C | 0x7C | 0xDC | 0xFC => {
+      Box::new(Alias(op, IGN(AbsoluteX(word()))))
+    }
+    _ => return None,
+  };
+
+  Some(instruction)
+}
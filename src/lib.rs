@@ -0,0 +1,17 @@
+//! A 6502 emulator, covering the NMOS 6502, the NES's Ricoh 2A03 and the WDC
+//! 65C02, with an assembler and disassembler.
+
+pub mod asm;
+pub mod bus;
+pub mod cpu;
+pub mod disasm;
+pub mod instructions;
+pub mod memory;
+pub mod program;
+pub mod registers;
+pub mod trace;
+
+pub use bus::Bus;
+pub use cpu::Cpu;
+pub use memory::Memory;
+pub use registers::Registers;
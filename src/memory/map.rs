@@ -0,0 +1,221 @@
+use crate::bus::Bus;
+
+type ReadCallback = Box<dyn FnMut(u16) -> u8>;
+type WriteCallback = Box<dyn FnMut(u16, u8)>;
+
+enum Backing {
+  Ram(Vec<u8>),
+  Rom(Vec<u8>),
+  Io(ReadCallback, WriteCallback),
+}
+
+struct Region {
+  start: u16,
+  end: u16,
+  backing: Backing,
+}
+
+impl Region {
+  fn contains(&self, addr: u16) -> bool {
+    self.start <= addr && addr <= self.end
+  }
+}
+
+// RAM and ROM repeat across their range when it is larger than the data.
+fn mirror(start: u16, addr: u16, len: usize) -> usize {
+  usize::from(addr - start) % len
+}
+
+// Address space composed of RAM, ROM and I/O regions. Regions are matched in
+// the order they were added; unmapped addresses read as zero and ignore
+// writes.
+pub struct MemoryMap {
+  regions: Vec<Region>,
+  rom_write: Option<WriteCallback>,
+}
+
+impl MemoryMap {
+  pub fn builder() -> MemoryMapBuilder {
+    MemoryMapBuilder {
+      map: MemoryMap {
+        regions: Vec::new(),
+        rom_write: None,
+      },
+    }
+  }
+
+  fn region(&self, addr: u16) -> Option<usize> {
+    self.regions.iter().position(|region| region.contains(addr))
+  }
+}
+
+impl Bus for MemoryMap {
+  fn read(&mut self, addr: u16) -> u8 {
+    let index = match self.region(addr) {
+      Some(index) => index,
+      None => return 0,
+    };
+    let region = &mut self.regions[index];
+    match &mut region.backing {
+      Backing::Io(read, _) => read(addr),
+      _ => self.peek(addr),
+    }
+  }
+
+  fn write(&mut self, addr: u16, value: u8) {
+    let index = match self.region(addr) {
+      Some(index) => index,
+      None => return,
+    };
+    let region = &mut self.regions[index];
+    match &mut region.backing {
+      Backing::Ram(data) => {
+        let offset = mirror(region.start, addr, data.len());
+        data[offset] = value;
+      }
+      Backing::Rom(_) => {
+        if let Some(rom_write) = &mut self.rom_write {
+          rom_write(addr, value);
+        }
+      }
+      Backing::Io(_, write) => write(addr, value),
+    }
+  }
+
+  // I/O regions cannot be read without side effects, so they peek as zero.
+  fn peek(&self, addr: u16) -> u8 {
+    let region = match self.region(addr) {
+      Some(index) => &self.regions[index],
+      None => return 0,
+    };
+    match &region.backing {
+      Backing::Ram(data) | Backing::Rom(data) => {
+        data[mirror(region.start, addr, data.len())]
+      }
+      Backing::Io(..) => 0,
+    }
+  }
+}
+
+pub struct MemoryMapBuilder {
+  map: MemoryMap,
+}
+
+impl MemoryMapBuilder {
+  // Zeroed RAM of `size` bytes, mirrored across `start..=end`.
+  pub fn ram(self, start: u16, end: u16, size: usize) -> Self {
+    assert!(size > 0, "RAM region must not be empty.");
+    self.region(start, end, Backing::Ram(vec![0; size]))
+  }
+
+  // Read-only `data`, mirrored across `start..=end`.
+  pub fn rom(self, start: u16, end: u16, data: &[u8]) -> Self {
+    assert!(!data.is_empty(), "ROM region must not be empty.");
+    self.region(start, end, Backing::Rom(data.to_vec()))
+  }
+
+  // Device registers, handled by callbacks given the full address.
+  pub fn io<R, W>(self, start: u16, end: u16, read: R, write: W) -> Self
+  where
+    R: FnMut(u16) -> u8 + 'static,
+    W: FnMut(u16, u8) + 'static,
+  {
+    self.region(start, end, Backing::Io(Box::new(read), Box::new(write)))
+  }
+
+  // Called with each write to ROM, which is otherwise dropped silently.
+  pub fn on_rom_write<W>(mut self, write: W) -> Self
+  where
+    W: FnMut(u16, u8) + 'static,
+  {
+    self.map.rom_write = Some(Box::new(write));
+    self
+  }
+
+  pub fn build(self) -> MemoryMap {
+    self.map
+  }
+
+  fn region(mut self, start: u16, end: u16, backing: Backing) -> Self {
+    assert!(start <= end, "Region must not end before it starts.");
+    self.map.regions.push(Region {
+      start,
+      end,
+      backing,
+    });
+    self
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::cell::RefCell;
+  use std::rc::Rc;
+
+  #[test]
+  fn ram_mirrors_across_its_range() {
+    let mut map = MemoryMap::builder().ram(0x0000, 0x1FFF, 0x0800).build();
+
+    map.write(0x0801, 0x42);
+    assert_eq!(map.read(0x0001), 0x42);
+    assert_eq!(map.peek(0x1801), 0x42);
+  }
+
+  #[test]
+  fn rom_ignores_writes_and_reports_them() {
+    let writes = Rc::new(RefCell::new(Vec::new()));
+    let log = Rc::clone(&writes);
+    let mut map = MemoryMap::builder()
+      .rom(0x8000, 0xFFFF, &[0x11, 0x22])
+      .on_rom_write(move |addr, value| log.borrow_mut().push((addr, value)))
+      .build();
+
+    map.write(0x8001, 0x99);
+    assert_eq!(map.read(0x8001), 0x22);
+    assert_eq!(map.read(0x8003), 0x22);
+    assert_eq!(*writes.borrow(), vec![(0x8001, 0x99)]);
+  }
+
+  #[test]
+  fn io_dispatches_to_callbacks() {
+    let writes = Rc::new(RefCell::new(Vec::new()));
+    let log = Rc::clone(&writes);
+    let mut map = MemoryMap::builder()
+      .io(
+        0x2000,
+        0x3FFF,
+        |addr| (addr & 0x07) as u8,
+        move |addr, value| log.borrow_mut().push((addr, value)),
+      )
+      .build();
+
+    assert_eq!(map.read(0x2003), 0x03);
+    assert_eq!(map.read(0x200F), 0x07);
+    // Peeking must not reach the device.
+    assert_eq!(map.peek(0x2003), 0x00);
+    map.write(0x2001, 0x1E);
+    assert_eq!(*writes.borrow(), vec![(0x2001, 0x1E)]);
+  }
+
+  #[test]
+  fn first_region_added_wins() {
+    let mut map = MemoryMap::builder()
+      .rom(0x0000, 0x00FF, &[0xAA])
+      .ram(0x0000, 0xFFFF, 0x10000)
+      .build();
+
+    map.write(0x0010, 0x55);
+    assert_eq!(map.read(0x0010), 0xAA);
+    map.write(0x0100, 0x55);
+    assert_eq!(map.read(0x0100), 0x55);
+  }
+
+  #[test]
+  fn unmapped_reads_zero_and_ignores_writes() {
+    let mut map = MemoryMap::builder().ram(0x0000, 0x00FF, 0x100).build();
+
+    map.write(0x1234, 0x55);
+    assert_eq!(map.read(0x1234), 0x00);
+  }
+}
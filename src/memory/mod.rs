@@ -0,0 +1,38 @@
+use crate::bus::Bus;
+
+pub mod map;
+
+const SIZE: usize = 0x10000;
+
+// Flat RAM covering the whole address space.
+pub struct Memory {
+  inner: [u8; SIZE],
+}
+
+impl Memory {
+  pub fn new() -> Self {
+    Memory { inner: [0; SIZE] }
+  }
+}
+
+impl Default for Memory {
+  fn default() -> Self {
+    Memory::new()
+  }
+}
+
+impl Bus for Memory {
+  fn read(&mut self, addr: u16) -> u8 {
+    self.peek(addr)
+  }
+
+  fn write(&mut self, addr: u16, value: u8) {
+    let checked = usize::from(addr);
+    self.inner[checked] = value;
+  }
+
+  fn peek(&self, addr: u16) -> u8 {
+    let checked = usize::from(addr);
+    self.inner[checked]
+  }
+}
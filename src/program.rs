@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use crate::asm::{Assembly, ErrorKind};
+use crate::instructions::Instruction;
+
+type Resolve = Box<dyn Fn(u16) -> Result<Box<dyn Instruction>, ErrorKind>>;
+
+enum Item {
+  Instruction(Box<dyn Instruction>),
+  Data(Vec<u8>),
+  // An instruction built once the address of the label is known.
+  Reference(String, Resolve),
+}
+
+// Collects typed instructions into a binary image, laid out from `origin`.
+// Each instruction is sized by rendering it, and instructions referring to a
+// label are sized from `instruction(0).render().len()` before the label has
+// an address.
+//
+//   let program = ProgramBuilder::new(0x0600)
+//     .label("loop")
+//     .push(DEX)
+//     .branch("loop", BNE)
+//     .absolute("done", |address| JMP(Absolute(address)))
+//     .label("done")
+//     .push(RTS)
+//     .build()?;
+pub struct ProgramBuilder {
+  origin: u16,
+  pc: u16,
+  items: Vec<Item>,
+  labels: HashMap<String, u16>,
+  duplicate: Option<String>,
+}
+
+impl ProgramBuilder {
+  pub fn new(origin: u16) -> Self {
+    ProgramBuilder {
+      origin,
+      pc: origin,
+      items: Vec::new(),
+      labels: HashMap::new(),
+      duplicate: None,
+    }
+  }
+
+  pub fn push<I: Instruction + 'static>(mut self, instruction: I) -> Self {
+    self.advance(instruction.render().len());
+    self.items.push(Item::Instruction(Box::new(instruction)));
+    self
+  }
+
+  pub fn data(mut self, bytes: &[u8]) -> Self {
+    self.advance(bytes.len());
+    self.items.push(Item::Data(bytes.to_vec()));
+    self
+  }
+
+  // Name the current address.
+  pub fn label(mut self, label: &str) -> Self {
+    if self.labels.insert(label.to_string(), self.pc).is_some() {
+      self.duplicate.get_or_insert_with(|| label.to_string());
+    }
+    self
+  }
+
+  // An instruction taking the label's address, e.g. `JSR` or
+  // `|address| LDA(AbsoluteX(address))`.
+  pub fn absolute<I, F>(self, label: &str, instruction: F) -> Self
+  where
+    I: Instruction + 'static,
+    F: Fn(u16) -> I + 'static,
+  {
+    let length = instruction(0).render().len();
+    self.reference(label, length, move |target| {
+      Ok(Box::new(instruction(target)))
+    })
+  }
+
+  // A branch to the label, e.g. `BNE`.
+  pub fn branch<I, F>(self, label: &str, instruction: F) -> Self
+  where
+    I: Instruction + 'static,
+    F: Fn(i8) -> I + 'static,
+  {
+    let length = instruction(0).render().len();
+    let next = self.pc.wrapping_add(length as u16);
+    self.reference(label, length, move |target| {
+      // Branches wrap around the address space like the program counter.
+      let offset = target.wrapping_sub(next) as i16;
+      let offset = i8::try_from(offset)
+        .map_err(|_| ErrorKind::BranchOutOfRange(i32::from(offset)))?;
+      Ok(Box::new(instruction(offset)))
+    })
+  }
+
+  // Render every instruction, now that all labels have addresses.
+  pub fn build(self) -> Result<Assembly, ErrorKind> {
+    if let Some(label) = self.duplicate {
+      return Err(ErrorKind::DuplicateLabel(label));
+    }
+
+    let mut bytes = Vec::new();
+    for item in &self.items {
+      match item {
+        Item::Instruction(instruction) => bytes.extend(instruction.render()),
+        Item::Data(data) => bytes.extend(data),
+        Item::Reference(label, resolve) => {
+          let target = *self
+            .labels
+            .get(label)
+            .ok_or_else(|| ErrorKind::UndefinedLabel(label.clone()))?;
+          bytes.extend(resolve(target)?.render());
+        }
+      }
+    }
+
+    Ok(Assembly {
+      origin: self.origin,
+      bytes,
+      symbols: self.labels.into_iter().collect(),
+    })
+  }
+
+  fn reference<F>(mut self, label: &str, length: usize, resolve: F) -> Self
+  where
+    F: Fn(u16) -> Result<Box<dyn Instruction>, ErrorKind> + 'static,
+  {
+    self.advance(length);
+    self
+      .items
+      .push(Item::Reference(label.to_string(), Box::new(resolve)));
+    self
+  }
+
+  fn advance(&mut self, length: usize) {
+    self.pc = self.pc.wrapping_add(length as u16);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::instructions::addressing::Absolute;
+  use crate::instructions::{BNE, DEX, JMP, RTS};
+
+  #[test]
+  fn builds_the_documented_example() {
+    let program = ProgramBuilder::new(0x0600)
+      .label("loop")
+      .push(DEX)
+      .branch("loop", BNE)
+      .absolute("done", |address| JMP(Absolute(address)))
+      .label("done")
+      .push(RTS)
+      .build()
+      .unwrap();
+
+    assert_eq!(program.origin, 0x0600);
+    assert_eq!(program.bytes, [0xCA, 0xD0, 0xFD, 0x4C, 0x06, 0x06, 0x60]);
+    assert_eq!(program.symbols["loop"], 0x0600);
+    assert_eq!(program.symbols["done"], 0x0606);
+  }
+
+  #[test]
+  fn branches_wrap_around_the_address_space() {
+    let program = ProgramBuilder::new(0xFFF0)
+      .branch("start", BNE)
+      .data(&[0; 14])
+      .label("start")
+      .build()
+      .unwrap();
+    assert_eq!(program.bytes[..2], [0xD0, 0x0E]);
+    assert_eq!(program.symbols["start"], 0x0000);
+  }
+
+  #[test]
+  fn distant_branches_are_rejected() {
+    let error = ProgramBuilder::new(0x0600)
+      .branch("far", BNE)
+      .data(&[0; 0x100])
+      .label("far")
+      .build()
+      .unwrap_err();
+    assert_eq!(error, ErrorKind::BranchOutOfRange(0x100));
+  }
+
+  #[test]
+  fn labels_are_defined_once() {
+    let error = ProgramBuilder::new(0x0600)
+      .label("loop")
+      .push(DEX)
+      .label("loop")
+      .build()
+      .unwrap_err();
+    assert_eq!(error, ErrorKind::DuplicateLabel("loop".to_string()));
+  }
+}
@@ -1,5 +1,5 @@
 #[derive(Default)]
-pub(crate) struct Registers {
+pub struct Registers {
   pub pc: ProgramCounter,
   pub sp: StackPointer,
   pub acc: Accumulator,
@@ -21,7 +21,7 @@ impl std::fmt::Display for Registers {
   }
 }
 
-pub(crate) trait Register {
+pub trait Register {
   fn raw(&self) -> u16;
 }
 
@@ -31,14 +31,14 @@ impl std::fmt::Display for dyn Register {
   }
 }
 
-pub(crate) trait IndexRegister {
-  fn read(&self) -> i8;
+pub trait IndexRegister {
+  fn read(&self) -> u8;
 
-  fn write(&mut self, value: i8);
+  fn write(&mut self, value: u8);
 }
 
 #[derive(Default)]
-pub(crate) struct ProgramCounter {
+pub struct ProgramCounter {
   pub value: u16,
 }
 impl Register for ProgramCounter {
@@ -47,7 +47,7 @@ impl Register for ProgramCounter {
   }
 }
 
-pub(crate) struct StackPointer {
+pub struct StackPointer {
   pub value: u8,
 }
 impl Default for StackPointer {
@@ -58,103 +58,120 @@ impl Default for StackPointer {
 
 impl Register for StackPointer {
   fn raw(&self) -> u16 {
-    self.value as u16
+    u16::from(self.value)
   }
 }
 
 #[derive(Default)]
-pub(crate) struct Accumulator {
-  pub value: i8,
+pub struct Accumulator {
+  pub value: u8,
 }
 impl Register for Accumulator {
   fn raw(&self) -> u16 {
-    self.value as u16
+    u16::from(self.value)
   }
 }
 
 #[derive(Default)]
-pub(crate) struct IndexX {
-  pub value: i8,
+pub struct IndexX {
+  pub value: u8,
 }
 impl Register for IndexX {
   fn raw(&self) -> u16 {
-    self.value as u16
+    u16::from(self.value)
   }
 }
 impl IndexRegister for IndexX {
-  fn read(&self) -> i8 {
+  fn read(&self) -> u8 {
     self.value
   }
 
-  fn write(&mut self, value: i8) {
+  fn write(&mut self, value: u8) {
     self.value = value;
   }
 }
 
 #[derive(Default)]
-pub(crate) struct IndexY {
-  pub value: i8,
+pub struct IndexY {
+  pub value: u8,
 }
 impl Register for IndexY {
   fn raw(&self) -> u16 {
-    self.value as u16
+    u16::from(self.value)
   }
 }
 impl IndexRegister for IndexY {
-  fn read(&self) -> i8 {
+  fn read(&self) -> u8 {
     self.value
   }
 
-  fn write(&mut self, value: i8) {
+  fn write(&mut self, value: u8) {
     self.value = value;
   }
 }
 
 #[derive(Default)]
-pub(crate) struct Flags {
+pub struct Flags {
   pub carry: bool,
   pub zero: bool,
   pub interrupt_disable: bool,
   pub decimal_mode: bool,
-  pub break_command: bool,
   pub overflow: bool,
   pub negative: bool,
 }
 impl Register for Flags {
   fn raw(&self) -> u16 {
-    let mut output = 0;
-    output &= u16::from(self.carry);
-    output <<= 1;
-    output &= u16::from(self.zero);
-    output <<= 1;
-    output &= u16::from(self.interrupt_disable);
-    output <<= 1;
-    output &= u16::from(self.decimal_mode);
-    output <<= 1;
-    output &= u16::from(self.break_command);
-    output <<= 1;
-    output &= u16::from(self.overflow);
-    output <<= 1;
-    output &= u16::from(self.negative);
-
-    output
+    u16::from(self.to_byte(false))
   }
 }
 
+const CARRY: u8 = 1 << 0;
+const ZERO: u8 = 1 << 1;
+const INTERRUPT_DISABLE: u8 = 1 << 2;
+const DECIMAL_MODE: u8 = 1 << 3;
+const BREAK: u8 = 1 << 4;
+const UNUSED: u8 = 1 << 5;
+const OVERFLOW: u8 = 1 << 6;
+const NEGATIVE: u8 = 1 << 7;
+
 impl Flags {
-  pub(crate) fn write(&mut self, mut value: u8) {
-    self.negative = (value & 1) != 0;
-    value >>= 1;
-    self.overflow = (value & 1) != 0;
-    value >>= 1;
-    self.break_command = (value & 1) != 0;
-    value >>= 1;
-    self.decimal_mode = (value & 1) != 0;
-    value >>= 1;
-    self.interrupt_disable = (value & 1) != 0;
-    value >>= 1;
-    self.zero = (value & 1) != 0;
-    value >>= 1;
-    self.carry = (value & 1) != 0;
+  // Status byte in NV-BDIZC order. Bit 5 always reads as set, and B only
+  // exists on the stack, set when pushed by PHP or BRK.
+  pub fn to_byte(&self, brk: bool) -> u8 {
+    let mut output = UNUSED;
+    let bits = [
+      (self.carry, CARRY),
+      (self.zero, ZERO),
+      (self.interrupt_disable, INTERRUPT_DISABLE),
+      (self.decimal_mode, DECIMAL_MODE),
+      (brk, BREAK),
+      (self.overflow, OVERFLOW),
+      (self.negative, NEGATIVE),
+    ];
+    for (set, bit) in bits.iter() {
+      if *set {
+        output |= bit;
+      }
+    }
+
+    output
+  }
+
+  // Most instructions set Z and N from the value they produce.
+  pub fn update_zero_negative(&mut self, value: u8) {
+    self.zero = value == 0;
+    self.negative = (value & NEGATIVE) != 0;
+  }
+
+  // B and bit 5 are not stored, so they are dropped when pulled.
+  pub fn from_byte(value: u8) -> Self {
+    Flags {
+      carry: (value & CARRY) != 0,
+      zero: (value & ZERO) != 0,
+      interrupt_disable: (value & INTERRUPT_DISABLE) != 0,
+      decimal_mode: (value & DECIMAL_MODE) != 0,
+      overflow: (value & OVERFLOW) != 0,
+      negative: (value & NEGATIVE) != 0,
+    }
   }
 }
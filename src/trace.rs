@@ -0,0 +1,30 @@
+use crate::bus::Bus;
+use crate::disasm::disassemble_one;
+use crate::instructions::interp::Variant;
+use crate::registers::Registers;
+
+// One line of an execution trace in the format of the nestest log, taken
+// before the instruction at the program counter runs:
+//
+//   C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD CYC:7
+pub fn line<B: Bus>(
+  bus: &B,
+  registers: &Registers,
+  cycles: u64,
+  variant: Variant,
+  undocumented_opcodes: bool,
+) -> String {
+  let instruction =
+    disassemble_one(bus, registers.pc.value, variant, undocumented_opcodes);
+
+  format!(
+    "{:<48}A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X} CYC:{}",
+    instruction.to_string(),
+    registers.acc.value,
+    registers.x.value,
+    registers.y.value,
+    registers.flags.to_byte(false),
+    registers.sp.value,
+    cycles
+  )
+}
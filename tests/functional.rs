@@ -0,0 +1,156 @@
+use std::fs;
+use std::path::PathBuf;
+
+use sixtyfiveohtwo::asm::assemble;
+use sixtyfiveohtwo::disasm::disassemble_one;
+use sixtyfiveohtwo::instructions::interp::Variant;
+use sixtyfiveohtwo::{trace, Bus, Cpu};
+
+// The suites are not distributed with the crate, see tests/data/README.md.
+// These tests are ignored by default and fail when asked to run without them.
+fn data(name: &str) -> Vec<u8> {
+  let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests", "data", name]
+    .iter()
+    .collect();
+  fs::read(&path)
+    .unwrap_or_else(|error| panic!("Cannot read {}: {}", path.display(), error))
+}
+
+// Klaus Dormann's 6502_functional_test, assembled with the default options.
+// It loops on itself at the address of whichever check failed, or at the
+// success trap once every check has passed.
+const FUNCTIONAL_START: u16 = 0x0400;
+const FUNCTIONAL_SUCCESS: u16 = 0x3469;
+const FUNCTIONAL_CYCLE_LIMIT: u64 = 200_000_000;
+
+#[test]
+#[ignore = "needs tests/data/6502_functional_test.bin"]
+fn klaus_dormann_functional_test() {
+  let image = data("6502_functional_test.bin");
+
+  let mut cpu = Cpu::new();
+  cpu.load(0x0000, &image);
+  cpu.registers.pc.value = FUNCTIONAL_START;
+
+  let pc = run_to_trap(&mut cpu, FUNCTIONAL_CYCLE_LIMIT);
+  let line = disassemble_one(&cpu.bus, pc, cpu.variant, false);
+  assert_eq!(
+    pc, FUNCTIONAL_SUCCESS,
+    "Trapped at ${:04X} after {} cycles: {}",
+    pc, cpu.cycles, line
+  );
+}
+
+// Step until an instruction jumps or branches to itself, returning where.
+fn run_to_trap(cpu: &mut Cpu, cycle_limit: u64) -> u16 {
+  loop {
+    let pc = cpu.registers.pc.value;
+    cpu.step().unwrap_or_else(|error| panic!("{}", error));
+
+    if cpu.registers.pc.value == pc {
+      return pc;
+    }
+    assert!(
+      cpu.cycles < cycle_limit,
+      "No trap after {} cycles, at ${:04X}",
+      cpu.cycles,
+      pc
+    );
+  }
+}
+
+// A shorter program in the same manner that is kept with the crate, see
+// tests/programs/checks.s. A failed check calls `fail`, so the return
+// address on the stack tells which one it was.
+const CHECKS: &str = include_str!("programs/checks.s");
+const CHECKS_CYCLE_LIMIT: u64 = 10_000;
+
+#[test]
+fn self_checking_program() {
+  let assembly = assemble(CHECKS).unwrap_or_else(|error| panic!("{}", error));
+  let symbol = |name: &str| assembly.symbols[name];
+
+  let mut cpu = Cpu::new();
+  cpu.load(assembly.origin, &assembly.bytes);
+  cpu.registers.pc.value = symbol("start");
+
+  let pc = run_to_trap(&mut cpu, CHECKS_CYCLE_LIMIT);
+  if pc == symbol("fail") {
+    let sp = cpu.registers.sp.value;
+    let caller = u16::from_le_bytes([
+      cpu.bus.peek(0x0100 + u16::from(sp.wrapping_add(1))),
+      cpu.bus.peek(0x0100 + u16::from(sp.wrapping_add(2))),
+    ])
+    .wrapping_sub(2);
+    let (check, _) = assembly
+      .symbols
+      .iter()
+      .filter(|(_, &address)| address <= caller)
+      .max_by_key(|(_, &address)| address)
+      .expect("A failed check follows a label");
+    panic!("Check `{}` failed at ${:04X}", check, caller);
+  }
+  assert_eq!(pc, symbol("pass"), "Trapped at ${:04X}", pc);
+}
+
+// The parts of a nestest log line this CPU can reproduce. The reference log
+// also annotates operands with memory contents and shows PPU timing, which
+// are dropped.
+fn nestest_fields(line: &str) -> Option<String> {
+  let registers = line.find("A:")?;
+  let cycles = line.find("CYC:")?;
+
+  Some(format!(
+    "{}  {:<8}  {}{}",
+    line.get(..4)?,
+    line.get(6..14)?.trim_end(),
+    line.get(registers..registers + 25)?,
+    &line[cycles..]
+  ))
+}
+
+#[test]
+#[ignore = "needs tests/data/nestest.nes and tests/data/nestest.log"]
+fn nestest() {
+  let rom = data("nestest.nes");
+  let log = data("nestest.log");
+
+  // iNES image: a 16 byte header, then one 16 KiB PRG bank that is mirrored
+  // at $8000 and $C000.
+  let prg = &rom[16..16 + 0x4000];
+  let mut cpu = Cpu::new();
+  cpu.variant = Variant::Ricoh2A03;
+  cpu.undocumented_opcodes = true;
+  cpu.load(0x8000, prg);
+  cpu.load(0xC000, prg);
+
+  // Automated mode starts at $C000 with the state left by a reset.
+  cpu.reset();
+  cpu.registers.pc.value = 0xC000;
+  cpu.registers.flags.interrupt_disable = true;
+
+  let log = String::from_utf8_lossy(&log);
+  for (index, expected) in log.lines().enumerate() {
+    let actual = trace::line(
+      &cpu.bus,
+      &cpu.registers,
+      cpu.cycles,
+      cpu.variant,
+      cpu.undocumented_opcodes,
+    );
+    assert_eq!(
+      nestest_fields(&actual),
+      nestest_fields(expected),
+      "First divergence at line {}\nexpected: {}\n  actual: {}",
+      index + 1,
+      expected,
+      actual
+    );
+
+    cpu.step().unwrap_or_else(|error| panic!("{}", error));
+  }
+
+  // Both test groups report failures as a code in $02 and $03.
+  assert_eq!(cpu.bus.peek(0x0002), 0x00);
+  assert_eq!(cpu.bus.peek(0x0003), 0x00);
+}
@@ -0,0 +1,211 @@
+; Self-checking tests of the documented NMOS instructions, in the manner of
+; Klaus Dormann's functional test. A failed check calls `fail`, which loops
+; on itself, and reaching `pass` means every check held.
+
+        .org $0400
+start:  CLD
+        LDX #$FF
+        TXS
+        CLV
+
+; Loads set N and Z.
+load:   LDA #$00
+        BEQ load1
+        JSR fail
+load1:  LDA #$80
+        BMI load2
+        JSR fail
+load2:  LDX #$7F
+        BPL load3
+        JSR fail
+load3:  LDY #$01
+        BNE add
+        JSR fail
+
+; Binary addition, with overflow and carry out.
+add:    CLC
+        LDA #$7F
+        ADC #$01
+        BVS add1
+        JSR fail
+add1:   BCC add2
+        JSR fail
+add2:   CMP #$80
+        BEQ add3
+        JSR fail
+add3:   SEC
+        LDA #$FF
+        ADC #$00
+        BCS add4
+        JSR fail
+add4:   BEQ sub
+        JSR fail
+
+; Binary subtraction, with borrow and overflow.
+sub:    SEC
+        LDA #$50
+        SBC #$F0
+        BCC sub1
+        JSR fail
+sub1:   BVC sub2
+        JSR fail
+sub2:   CMP #$60
+        BEQ sub3
+        JSR fail
+sub3:   SEC
+        LDA #$50
+        SBC #$B0
+        BVS sub4
+        JSR fail
+sub4:   CMP #$A0
+        BEQ bcd
+        JSR fail
+
+; Decimal mode addition and subtraction.
+bcd:    SED
+        CLC
+        LDA #$58
+        ADC #$46
+        BCS bcd1
+        JSR fail
+bcd1:   CMP #$04
+        BEQ bcd2
+        JSR fail
+bcd2:   SEC
+        LDA #$00
+        SBC #$01
+        BCC bcd3
+        JSR fail
+bcd3:   CMP #$99
+        BEQ bcd4
+        JSR fail
+bcd4:   CLD
+
+; Comparisons set C when the register is not below the operand.
+compare: LDA #$40
+        CMP #$41
+        BCC compare1
+        JSR fail
+compare1: BMI compare2
+        JSR fail
+compare2: LDX #$40
+        CPX #$40
+        BEQ compare3
+        JSR fail
+compare3: BCS compare4
+        JSR fail
+compare4: LDY #$41
+        CPY #$40
+        BNE shift
+        JSR fail
+
+; Shifts and rotates through the carry.
+shift:  LDA #$81
+        ASL A
+        BCS shift1
+        JSR fail
+shift1: LSR A
+        BCC shift2
+        JSR fail
+shift2: SEC
+        ROR A
+        BCS shift3
+        JSR fail
+shift3: BMI shift4
+        JSR fail
+shift4: CLC
+        ROL A
+        BEQ shift5
+        JSR fail
+shift5: BCS memory
+        JSR fail
+
+; Read-modify-write and the indexed and indirect addressing modes.
+memory: LDA #$FF
+        STA $10
+        INC $10
+        BEQ memory1
+        JSR fail
+memory1: LDX #$02
+        DEC $0E,X
+        BMI memory2
+        JSR fail
+memory2: LDA #$00
+        STA $20
+        LDA #$03
+        STA $21
+        LDA #$56
+        STA $0300
+        LDA #$34
+        LDY #$03
+        STA ($20),Y
+        LDA $0303
+        CMP #$34
+        BEQ memory3
+        JSR fail
+memory3: LDX #$02
+        LDA ($1E,X)
+        CMP #$56
+        BEQ memory4
+        JSR fail
+memory4: LDX #$03
+        LDA $0300,X
+        CMP #$34
+        BEQ stack
+        JSR fail
+
+; The stack, for values, flags and subroutines.
+stack:  LDA #$A5
+        PHA
+        LDA #$00
+        PLA
+        CMP #$A5
+        BEQ stack1
+        JSR fail
+stack1: SEC
+        PHP
+        CLC
+        PLP
+        BCS stack2
+        JSR fail
+stack2: LDA #$00
+        JSR answer
+        CMP #$2A
+        BEQ stack3
+        JSR fail
+stack3: TSX
+        CPX #$FF
+        BEQ bits
+        JSR fail
+
+; BIT copies bits 7 and 6 of memory into N and V.
+bits:   LDA #$C0
+        STA $30
+        LDA #$01
+        BIT $30
+        BEQ bits1
+        JSR fail
+bits1:  BVS bits2
+        JSR fail
+bits2:  BMI interrupt
+        JSR fail
+
+; BRK skips its padding byte and RTI comes back after it.
+interrupt: LDA #$00
+        BRK
+        .byte $00
+        CMP #$77
+        BEQ pass
+        JSR fail
+
+pass:   JMP pass
+fail:   JMP fail
+
+answer: LDA #$2A
+        RTS
+
+handler: LDA #$77
+        RTI
+
+        .org $FFFE
+        .word handler
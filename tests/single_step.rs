@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use sixtyfiveohtwo::instructions::interp::{interp, Variant};
+use sixtyfiveohtwo::registers::Flags;
+use sixtyfiveohtwo::{Bus, Cpu, Memory};
+
+use serde_json::Value;
+
+// Tom Harte's ProcessorTests for the NMOS 6502, one file of vectors per
+// opcode, from https://github.com/SingleStepTests/65x02 (6502/v1). Each lists
+// the bus access of every cycle, so the CPU runs cycle accurate. They are
+// not distributed with the crate: place `00.json` to `ff.json` in
+// tests/data/6502/v1 and run `cargo test -- --ignored`.
+const HARTE: [&str; 4] = ["tests", "data", "6502", "v1"];
+
+// A few vectors in the same format, written by hand from the documented
+// behaviour of the NMOS part, for read-modify-write, branches, subroutines,
+// interrupts and decimal arithmetic.
+const VECTORS: [&str; 2] = ["tests", "vectors"];
+
+// Only the first few failures of each opcode are shown.
+const REPORTED: usize = 5;
+
+// Sparse RAM, as each vector only touches a handful of addresses, which
+// records every access the CPU makes.
+#[derive(Default)]
+struct TestBus {
+  ram: HashMap<u16, u8>,
+  activity: Vec<(u16, u8, &'static str)>,
+}
+
+impl Bus for TestBus {
+  fn read(&mut self, addr: u16) -> u8 {
+    let value = self.peek(addr);
+    self.activity.push((addr, value, "read"));
+    value
+  }
+
+  fn write(&mut self, addr: u16, value: u8) {
+    self.activity.push((addr, value, "write"));
+    self.ram.insert(addr, value);
+  }
+
+  fn peek(&self, addr: u16) -> u8 {
+    self.ram.get(&addr).copied().unwrap_or(0)
+  }
+}
+
+struct State {
+  pc: u16,
+  s: u8,
+  a: u8,
+  x: u8,
+  y: u8,
+  p: u8,
+  ram: Vec<(u16, u8)>,
+}
+
+fn number<T: TryFrom<u64>>(value: &Value, key: &str) -> T {
+  value
+    .get(key)
+    .and_then(Value::as_u64)
+    .and_then(|number| T::try_from(number).ok())
+    .unwrap_or_else(|| panic!("Missing or invalid `{}`", key))
+}
+
+fn state(value: &Value) -> State {
+  let ram = value
+    .get("ram")
+    .and_then(Value::as_array)
+    .expect("Missing `ram`")
+    .iter()
+    .map(|cell| {
+      let cell = cell.as_array().expect("Invalid `ram` entry");
+      let field = |index: usize| cell.get(index).and_then(Value::as_u64);
+      match (field(0), field(1)) {
+        (Some(addr), Some(value)) => (addr as u16, value as u8),
+        _ => panic!("Invalid `ram` entry"),
+      }
+    })
+    .collect();
+
+  State {
+    pc: number(value, "pc"),
+    s: number(value, "s"),
+    a: number(value, "a"),
+    x: number(value, "x"),
+    y: number(value, "y"),
+    p: number(value, "p"),
+    ram,
+  }
+}
+
+// One cycle of bus activity, as `[address, value, "read"]`.
+fn access(value: &Value) -> (u16, u8, &'static str) {
+  let cycle = value.as_array().expect("Invalid `cycles` entry");
+  let field = |index: usize| cycle.get(index).and_then(Value::as_u64);
+  let kind = match cycle.get(2).and_then(Value::as_str) {
+    Some("read") => "read",
+    Some("write") => "write",
+    _ => panic!("Invalid `cycles` entry"),
+  };
+  match (field(0), field(1)) {
+    (Some(addr), Some(value)) => (addr as u16, value as u8, kind),
+    _ => panic!("Invalid `cycles` entry"),
+  }
+}
+
+// B and bit 5 are not part of the register, only of the pushed byte.
+const STORED_FLAGS: u8 = !0x30;
+const FLAG_NAMES: [(u8, char); 6] = [
+  (0x80, 'N'),
+  (0x40, 'V'),
+  (0x08, 'D'),
+  (0x04, 'I'),
+  (0x02, 'Z'),
+  (0x01, 'C'),
+];
+
+// Run one vector, returning a description of each difference.
+fn run(vector: &Value) -> Vec<String> {
+  let initial = state(vector.get("initial").expect("Missing `initial`"));
+  let expected = state(vector.get("final").expect("Missing `final`"));
+  let activity = vector
+    .get("cycles")
+    .and_then(Value::as_array)
+    .expect("Missing `cycles`")
+    .iter()
+    .map(access)
+    .collect::<Vec<_>>();
+
+  let mut cpu = Cpu::with_bus(TestBus::default());
+  cpu.undocumented_opcodes = true;
+  cpu.cycle_accurate = true;
+  for &(addr, value) in &initial.ram {
+    cpu.bus.ram.insert(addr, value);
+  }
+  cpu.registers.pc.value = initial.pc;
+  cpu.registers.sp.value = initial.s;
+  cpu.registers.acc.value = initial.a;
+  cpu.registers.x.value = initial.x;
+  cpu.registers.y.value = initial.y;
+  cpu.registers.flags = Flags::from_byte(initial.p);
+
+  if let Err(error) = cpu.step() {
+    return vec![error.to_string()];
+  }
+
+  let mut mismatches = Vec::new();
+  let registers = [
+    ("pc", expected.pc, cpu.registers.pc.value),
+    (
+      "s",
+      u16::from(expected.s),
+      u16::from(cpu.registers.sp.value),
+    ),
+    (
+      "a",
+      u16::from(expected.a),
+      u16::from(cpu.registers.acc.value),
+    ),
+    ("x", u16::from(expected.x), u16::from(cpu.registers.x.value)),
+    ("y", u16::from(expected.y), u16::from(cpu.registers.y.value)),
+  ];
+  for &(name, expected, actual) in &registers {
+    if expected != actual {
+      mismatches.push(format!(
+        "{}: expected ${:02X}, got ${:02X}",
+        name, expected, actual
+      ));
+    }
+  }
+
+  let expected_p = expected.p & STORED_FLAGS;
+  let actual_p = cpu.registers.flags.to_byte(false) & STORED_FLAGS;
+  for &(bit, name) in &FLAG_NAMES {
+    if (expected_p & bit) != (actual_p & bit) {
+      mismatches.push(format!(
+        "flag {}: expected {}, got {}",
+        name,
+        u8::from(expected_p & bit != 0),
+        u8::from(actual_p & bit != 0)
+      ));
+    }
+  }
+
+  for &(addr, value) in &expected.ram {
+    let actual = cpu.bus.peek(addr);
+    if actual != value {
+      mismatches.push(format!(
+        "ram[${:04X}]: expected ${:02X}, got ${:02X}",
+        addr, value, actual
+      ));
+    }
+  }
+
+  if cpu.cycles != activity.len() as u64 {
+    mismatches.push(format!(
+      "cycles: expected {}, got {}",
+      activity.len(),
+      cpu.cycles
+    ));
+  }
+
+  // Only the first cycle to differ, as everything after tends to follow.
+  let cycle = (0..activity.len().max(cpu.bus.activity.len()))
+    .find(|&cycle| activity.get(cycle) != cpu.bus.activity.get(cycle));
+  if let Some(cycle) = cycle {
+    let show = |access: Option<&(u16, u8, &str)>| match access {
+      Some(&(addr, value, kind)) => {
+        format!("{} ${:02X} at ${:04X}", kind, value, addr)
+      }
+      None => "nothing".to_string(),
+    };
+    mismatches.push(format!(
+      "cycle {}: expected {}, got {}",
+      cycle + 1,
+      show(activity.get(cycle)),
+      show(cpu.bus.activity.get(cycle))
+    ));
+  }
+
+  mismatches
+}
+
+// Run every vector in `directory` for the opcodes the decoder accepts,
+// returning the number of files found and the failures.
+fn check(directory: &[&str]) -> (usize, Vec<String>) {
+  let directory: PathBuf = std::iter::once(env!("CARGO_MANIFEST_DIR"))
+    .chain(directory.iter().copied())
+    .collect();
+
+  let mut failures = Vec::new();
+  let mut files = 0;
+  for opcode in 0..=0xFFu8 {
+    // Only opcodes the decoder accepts have anything to test.
+    let mut memory = Memory::new();
+    memory.write(0x0000, opcode);
+    if interp(&memory, 0x0000, Variant::Nmos6502, true).is_err() {
+      continue;
+    }
+
+    let path = directory.join(format!("{:02x}.json", opcode));
+    if !path.exists() {
+      continue;
+    }
+    files += 1;
+
+    let failed = check_file(&path, opcode, &mut failures);
+    if failed > REPORTED {
+      failures.push(format!(
+        "${:02X}: {} more failures",
+        opcode,
+        failed - REPORTED
+      ));
+    }
+  }
+
+  (files, failures)
+}
+
+// Run the vectors in one file, returning how many failed.
+fn check_file(path: &Path, opcode: u8, failures: &mut Vec<String>) -> usize {
+  let text = fs::read_to_string(path)
+    .unwrap_or_else(|error| panic!("{}: {}", path.display(), error));
+  let vectors: Value = serde_json::from_str(&text)
+    .unwrap_or_else(|error| panic!("{}: {}", path.display(), error));
+  let vectors = vectors.as_array().expect("Expected an array of vectors");
+
+  let mut failed = 0;
+  for vector in vectors {
+    let mismatches = run(vector);
+    if mismatches.is_empty() {
+      continue;
+    }
+    failed += 1;
+    if failed <= REPORTED {
+      let name = vector.get("name").and_then(Value::as_str).unwrap_or("?");
+      failures.push(format!(
+        "${:02X} `{}`: {}",
+        opcode,
+        name,
+        mismatches.join(", ")
+      ));
+    }
+  }
+
+  failed
+}
+
+#[test]
+fn hand_written_vectors() {
+  let (files, failures) = check(&VECTORS);
+  assert!(files > 0, "No vectors found in tests/vectors");
+  assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+#[ignore = "needs tests/data/6502/v1"]
+fn single_step_vectors() {
+  let (files, failures) = check(&HARTE);
+  assert!(files > 0, "No vectors found in tests/data/6502/v1");
+  assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}